@@ -1,10 +1,30 @@
 use std::{io::{BufWriter, Write, Read, BufReader}, fs::File};
 
 use crate::parser::ExprParser;
+use crate::parser::errors::ParseError;
+use crate::parser::explain::explain;
 
 pub mod parser;
 
 fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    if let Some(i) = args.iter().position(|a| a == "--explain") {
+        match args.get(i + 1) {
+            Some(code) => match explain(code) {
+                Some(a) => print!("{}", a),
+                None => {
+                    eprintln!("error: {} is not a valid error code", code);
+                    std::process::exit(1);
+                },
+            },
+            None => {
+                eprintln!("error: --explain requires an error code (e.g. --explain E0001)");
+                std::process::exit(1);
+            },
+        }
+        return;
+    }
+
     init_logger();
     
     let mut writer = BufWriter::new(std::io::stdout().lock());
@@ -13,8 +33,12 @@ fn main() {
         let mut s: String = String::new();
         _ = write!(writer, "Enter file name > ");
         _ = writer.flush();
-        _ = std::io::stdin().read_line(&mut s);
-        match File::open(s.trim()) {
+        if let Ok(0) | Err(_) = std::io::stdin().read_line(&mut s) {
+            _ = writeln!(writer);
+            break;
+        }
+        let file_name = s.trim().to_string();
+        match File::open(&file_name) {
             Ok(a) => {
                 let mut stream = BufReader::new(a);
                 s.clear();
@@ -23,7 +47,7 @@ fn main() {
                 } else {
                     match parser.parse(&s) {
                         Ok(a) => _ = writeln!(writer, "Succeed: {}", a),
-                        Err(a) => error_writeln!(writer, a),
+                        Err(a) => write_errors(&mut writer, &a, &file_name, &s),
                     }
                     parser.clear_all();
                 }
@@ -33,6 +57,32 @@ fn main() {
     }
 }
 
+/// 見つかったすべてのエラーを表示し、最後にエラーの数をまとめて表示する関数です。
+/// - `writer` - 出力先
+/// - `errors` - 表示するエラー
+/// - `file_name` - ソースコードのファイル名
+/// - `source` - ソースコード全体
+fn write_errors(writer: &mut impl Write, errors: &[ParseError], file_name: &str, source: &str) {
+    for a in errors {
+        _ = writeln!(writer, "{}", a.diagnostic().render(file_name, source));
+    }
+    match errors.len() {
+        1 => _ = writeln!(writer, "error: aborting due to 1 previous error"),
+        n => _ = writeln!(writer, "error: aborting due to {} previous errors", n),
+    }
+    let mut codes: Vec<&str> = errors.iter().map(|a| a.code()).collect();
+    codes.sort_unstable();
+    codes.dedup();
+    match codes.as_slice() {
+        [] => {},
+        [a] => _ = writeln!(writer, "For more information about this error, try `--explain {}`.", a),
+        [a, ..] => {
+            _ = writeln!(writer, "Some errors have detailed explanations: {}.", codes.join(", "));
+            _ = writeln!(writer, "For more information about an error, try `--explain {}`.", a);
+        },
+    }
+}
+
 /// ロガーのセットアップをする関数です。
 fn init_logger() {
     let time = chrono::Local::now().format("%Y%m%d_%H%M%S");
@@ -0,0 +1,263 @@
+use std::collections::HashMap;
+
+use super::borrow::BorrowKind;
+use super::token::Span;
+
+/// 値をムーブして破棄する組み込みの関数の名前です。同じ名前の関数が定義されていれば、その関数を呼び出します。
+pub const BUILTIN_DROP: &str = "drop";
+
+/// プログラム全体を表す構造体です。
+#[derive(Debug, Clone)]
+pub struct Program {
+    /// 関数の外に書かれた文からなるブロック
+    pub main: Block,
+    /// 関数名ごとの関数の定義
+    pub functions: HashMap<String, Function>,
+    /// 構造体名ごとの構造体の定義
+    pub structs: HashMap<String, Struct>,
+}
+
+/// 関数の定義を表す構造体です。
+#[derive(Debug, Clone)]
+pub struct Function {
+    /// 関数名
+    pub name: String,
+    /// 宣言された寿命引数 (`<'a>`)
+    pub lifetimes: Vec<Lifetime>,
+    /// 引数の宣言
+    pub parameters: Vec<Declaration>,
+    /// 戻り値の型 (`-> T`、省略されていれば `None`)
+    pub output: Option<Type>,
+    /// 関数の本体
+    pub body: Block,
+    /// 関数名の範囲
+    pub span: Span,
+}
+
+/// 構造体の定義を表す構造体です。
+#[derive(Debug, Clone)]
+pub struct Struct {
+    /// 構造体名
+    pub name: String,
+    /// 定義された順のフィールド
+    pub fields: Vec<FieldDefinition>,
+    /// 構造体名の範囲
+    pub span: Span,
+    /// `#[derive(Copy)]` の `Copy` の範囲 (指定されていなければ `None`)
+    pub copy: Option<Span>,
+}
+
+/// 構造体の定義に含まれるフィールドを表す構造体です。
+#[derive(Debug, Clone)]
+pub struct FieldDefinition {
+    /// フィールド名
+    pub name: String,
+    /// フィールドの型
+    pub ty: Type,
+    /// フィールド名から型までの範囲
+    pub span: Span,
+}
+
+/// ブロックを表す構造体です。
+#[derive(Debug, Clone)]
+pub struct Block {
+    /// ブロック内の文
+    pub statements: Vec<Statement>,
+    /// ブロックの値となる末尾の式
+    pub tail: Option<Box<Expression>>,
+    /// ブロック内で宣言された変数
+    pub declarations: Vec<usize>,
+    /// ブロック全体の範囲
+    pub span: Span,
+    /// ブロックの終わり (閉じかっこ) の範囲
+    pub end: Span,
+}
+
+/// 文を表す列挙型です。
+#[derive(Debug, Clone)]
+pub enum Statement {
+    /// `let` による変数の宣言を表します。初期化式は、宣言した変数への代入式として保持します。
+    Let(Declaration, Option<Expression>),
+    /// `debug` による値の表示を表します。
+    Debug(Expression),
+    /// セミコロンで終わる式を表します。
+    Expression(Expression),
+}
+
+/// 変数の宣言を表す構造体です。
+#[derive(Debug, Clone)]
+pub struct Declaration {
+    /// 変数名
+    pub name: String,
+    /// 変数を識別する番号
+    pub binding: usize,
+    /// 変数名の範囲
+    pub span: Span,
+    /// 型注釈 (省略されていれば `None`)
+    pub ty: Option<Type>,
+    /// `mut` が付けられているか
+    pub mutable: bool,
+}
+
+/// 型注釈を表す構造体です。
+#[derive(Debug, Clone)]
+pub struct Type {
+    /// 型注釈全体の範囲
+    pub span: Span,
+    /// 型の種類
+    pub kind: TypeKind,
+}
+
+/// 型注釈の種類を定義します。
+#[derive(Debug, Clone)]
+pub enum TypeKind {
+    /// `i32` や `String` などの名前で表される型を表します。
+    Named(String),
+    /// 参照型 (`&'a T`, `&mut T`) を表します。寿命が省略されていれば `None` です。
+    Reference(Option<Lifetime>, BorrowKind, Box<Type>),
+    /// タプル型 (`(T, U)`) を表します。要素がなければ `()` です。
+    Tuple(Vec<Type>),
+    /// 配列型 (`[T; N]`) を表します。
+    Array(Box<Type>, usize),
+    /// 可変長の配列型 (`Vec<T>`) を表します。
+    Vec(Box<Type>),
+    /// ヒープ上の値を所有するポインタ型 (`Box<T>`) を表します。
+    Box(Box<Type>),
+}
+
+/// 寿命の名前 (`'a`) を表す構造体です。
+#[derive(Debug, Clone)]
+pub struct Lifetime {
+    /// 寿命の名前 (`'` を含む)
+    pub name: String,
+    /// 寿命の名前の範囲
+    pub span: Span,
+}
+
+/// 式を表す構造体です。
+#[derive(Debug, Clone)]
+pub struct Expression {
+    /// 式を識別する番号
+    pub id: usize,
+    /// 式全体の範囲
+    pub span: Span,
+    /// 式の種類
+    pub kind: ExpressionKind,
+}
+
+/// 式の種類を定義します。
+#[derive(Debug, Clone)]
+pub enum ExpressionKind {
+    /// 整数リテラルを表します。
+    Integer(i32),
+    /// 文字列リテラルを表します。
+    String(String),
+    /// 変数を表します。
+    Variable(Variable),
+    /// ブロックを表します。
+    Block(Block),
+    /// 参照を作成する単項式 (`&`, `&mut`) を表します。
+    Reference(BorrowKind, Box<Expression>),
+    /// 参照外し (`*`) を表します。
+    Dereference(Box<Expression>),
+    /// その他の単項式を表します。
+    Unary(String, Box<Expression>),
+    /// 二項式を表します。
+    Binary(String, Box<Expression>, Box<Expression>),
+    /// 代入式 (`=`, `+=` など) を表します。
+    Assign(String, Box<Expression>, Box<Expression>),
+    /// 条件分岐 (`if`) を表します。`else` 節はブロックまたは別の `if` 式です。
+    If(Box<Expression>, Block, Option<Box<Expression>>),
+    /// 条件を満たす間繰り返すループ (`while`) を表します。
+    While(Box<Expression>, Block),
+    /// `break` するまで繰り返すループ (`loop`) を表します。`break` の値がループの値になります。
+    Loop(Block),
+    /// ループからの脱出 (`break`) を表します。
+    Break(Option<Box<Expression>>),
+    /// ループの次の繰り返しへの移動 (`continue`) を表します。
+    Continue,
+    /// 関数呼び出しを表します。
+    Call(String, Vec<Expression>),
+    /// 関数からの脱出 (`return`) を表します。
+    Return(Option<Box<Expression>>),
+    /// 構造体式 (`Point { x: 1, y: 2 }`) を表します。フィールドは書かれた順に保持します。
+    Struct(String, Vec<FieldValue>),
+    /// フィールドへのアクセス (`a.b`) を表します。フィールド名とその範囲を保持します。タプルの要素 (`t.0`) も含みます。
+    Field(Box<Expression>, String, Span),
+    /// タプル式 (`(a, b)`) を表します。要素がなければ `()` です。
+    Tuple(Vec<Expression>),
+    /// 配列式 (`[a, b, c]`) を表します。
+    Array(Vec<Expression>),
+    /// 添字によるアクセス (`a[i]`) を表します。
+    Index(Box<Expression>, Box<Expression>),
+    /// 可変長の配列を作成する式 (`vec![a, b]`、`Vec::new()`) を表します。
+    Vec(Vec<Expression>),
+    /// メソッド呼び出し (`v.push(a)`) を表します。メソッド名とその範囲、引数を保持します。
+    MethodCall(Box<Expression>, String, Span, Vec<Expression>),
+    /// 値をヒープに移す式 (`Box::new(a)`) を表します。
+    Box(Box<Expression>),
+}
+
+/// 構造体式に含まれるフィールドの値を表す構造体です。
+#[derive(Debug, Clone)]
+pub struct FieldValue {
+    /// フィールド名
+    pub name: String,
+    /// フィールド名の範囲
+    pub span: Span,
+    /// フィールドの値
+    pub value: Expression,
+}
+
+/// 式の中で使用される変数を表す構造体です。
+#[derive(Debug, Clone)]
+pub struct Variable {
+    /// 変数名
+    pub name: String,
+    /// 参照している変数を識別する番号
+    pub binding: usize,
+}
+
+impl Type {
+    /// 型に含まれる参照を外側から順に列挙します。タプルの要素は左から順に列挙します。
+    /// 参照ごとに、寿命 (省略されていれば `None`) と `&` の範囲を返します。
+    pub fn references(&self) -> Vec<(Option<&Lifetime>, Span)> {
+        match &self.kind {
+            TypeKind::Named(_) => Vec::new(),
+            TypeKind::Reference(lifetime, _, inner) => {
+                let mut references = vec![(lifetime.as_ref(), Span { end: self.span.start + 1, ..self.span })];
+                references.extend(inner.references());
+                references
+            },
+            TypeKind::Tuple(a) => a.iter().flat_map(|a| a.references()).collect(),
+            TypeKind::Array(a, _) | TypeKind::Vec(a) | TypeKind::Box(a) => a.references(),
+        }
+    }
+}
+
+impl Struct {
+    /// フィールドの位置と定義を取得します。
+    /// - `name` - フィールド名
+    pub fn field(&self, name: &str) -> Option<(usize, &FieldDefinition)> {
+        self.fields.iter().enumerate().find(|(_, a)| a.name == name)
+    }
+}
+
+impl Expression {
+    /// 変数や参照外し、場所のフィールドや要素のように、場所を表す式であるかを取得します。
+    pub fn is_place(&self) -> bool {
+        match &self.kind {
+            ExpressionKind::Variable(_) | ExpressionKind::Dereference(_) => true,
+            ExpressionKind::Field(e, _, _) | ExpressionKind::Index(e, _) => e.is_place(),
+            _ => false,
+        }
+    }
+
+    /// ブロックや `if` 式のように、`;` なしで文として扱える式であるかを取得します。
+    pub fn is_block_like(&self) -> bool {
+        matches!(
+            self.kind,
+            ExpressionKind::Block(_) | ExpressionKind::If(_, _, _) | ExpressionKind::While(_, _) | ExpressionKind::Loop(_)
+        )
+    }
+}
@@ -0,0 +1,1257 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fmt::Display;
+
+use log::{info, trace};
+
+use crate::ret_err;
+
+use super::ast::{Block, BUILTIN_DROP, Declaration, Expression, ExpressionKind, Function, Program, Statement, Struct, Type, TypeKind, Variable};
+use super::errors::{BorrowError, BorrowedAccessError, BorrowedMoveError, DanglingReferenceError, LifetimeError, MoveBehindReferenceError, MoveOutError, MovedValueError, MutabilityError, ParseError, ReferenceError, ReturnLocalReferenceError};
+use super::lifetimes::{self, Region};
+use super::liveness::Liveness;
+use super::token::Span;
+
+/// 借用の種類を定義します。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BorrowKind {
+    /// 共有参照 (`&`) による借用を表します。
+    Shared,
+    /// 可変参照 (`&mut`) による借用を表します。
+    Mutable,
+}
+
+/// 変数に対する一つの借用を表す構造体です。
+#[derive(Debug, Clone)]
+struct Loan {
+    /// 借用されている変数を識別する番号
+    binding: usize,
+    /// 借用されているフィールドの経路 (変数全体であれば空)
+    path: Vec<String>,
+    /// 借用されている場所 (`a` や `a.b`)
+    name: String,
+    /// 借用の種類
+    kind: BorrowKind,
+    /// 借用が作成された範囲
+    at: Span,
+    /// 参照をたどった先の場所に対する借用であるか (参照を保持する変数が破棄されても借用は有効です)
+    reborrowed: bool,
+    /// 引数が受け取った参照の、呼び出し側の借用を表す場合の寿命と寿命が書かれた範囲
+    region: Option<(Region, Span)>,
+}
+
+/// 場所の使われ方を定義します。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Access {
+    /// 値を読み出し、ムーブされる種類の値であればムーブします。
+    Move,
+    /// 値をムーブせずに読み出します。
+    Read,
+    /// 借用や代入、メソッドの受け取り側として場所のみを使用し、値は読み出しません。
+    Place,
+}
+
+/// 借用や代入の対象となる場所を表す構造体です。
+#[derive(Debug)]
+struct Target<'e> {
+    /// 場所の始まりの変数
+    variable: &'e Variable,
+    /// 変数からのフィールドの経路 (参照外しは経路に含めず、参照や `Box` を保持する場所の一部とみなします)
+    path: Vec<String>,
+    /// 場所を `*a.b` の形式で表した文字列
+    name: String,
+    /// 場所に至るまでに最初にたどった参照の種類 (参照をたどらなければ `None`)
+    through: Option<BorrowKind>,
+}
+
+/// 静的解析で扱う値の種類を定義します。
+#[derive(Debug, Clone, PartialEq)]
+enum ValueKind {
+    /// 種類が分からない値を表します。
+    Unknown,
+    /// 型注釈のない仮引数のように、どの種類の値でもあり得る値を表します。
+    Any,
+    /// 整数を表します。
+    Integer,
+    /// 文字列を表します。
+    String,
+    /// 参照を表します。
+    Reference(BorrowKind, Box<ValueKind>),
+    /// 構造体を、構造体名で表します。
+    Struct(String),
+    /// タプルを表します。
+    Tuple(Vec<ValueKind>),
+    /// 配列を、要素の種類と要素の数で表します。
+    Array(Box<ValueKind>, usize),
+    /// 可変長の配列を、要素の種類で表します。
+    Vec(Box<ValueKind>),
+    /// ヒープ上の値を所有するポインタを、指す値の種類で表します。
+    Box(Box<ValueKind>),
+}
+
+/// 型注釈の型が、代入や受け渡しの際にムーブされずに複製される (`Copy` である) 型であるかを取得します。
+/// 所有権と借用の検査と同じ基準で判定します。
+/// - `ty` - 型注釈
+/// - `structs` - 構造体名ごとの構造体の定義
+pub fn is_copy(ty: &Type, structs: &HashMap<String, Struct>) -> bool {
+    !ValueKind::from_type(ty).is_move_type(structs)
+}
+
+/// 場所の経路で、配列の要素を表す名前です。添字によらず、すべての要素を同じ場所とみなします。
+const INDEX: &str = "[_]";
+
+impl ValueKind {
+    /// 型注釈から値の種類を求めます。型検査を通過した型注釈のみを扱うため、その他の名前は構造体とします。
+    /// - `ty` - 型注釈
+    fn from_type(ty: &Type) -> ValueKind {
+        match &ty.kind {
+            TypeKind::Named(a) if a == "i32" => ValueKind::Integer,
+            TypeKind::Named(a) if a == "String" || a == "str" => ValueKind::String,
+            TypeKind::Named(a) => ValueKind::Struct(a.clone()),
+            TypeKind::Reference(_, kind, a) => ValueKind::Reference(*kind, Box::new(ValueKind::from_type(a))),
+            TypeKind::Tuple(a) if a.is_empty() => ValueKind::Unknown,
+            TypeKind::Tuple(a) => ValueKind::Tuple(a.iter().map(ValueKind::from_type).collect()),
+            TypeKind::Array(a, length) => ValueKind::Array(Box::new(ValueKind::from_type(a)), *length),
+            TypeKind::Vec(a) => ValueKind::Vec(Box::new(ValueKind::from_type(a))),
+            TypeKind::Box(a) => ValueKind::Box(Box::new(ValueKind::from_type(a))),
+        }
+    }
+
+    /// 代入や受け渡しの際にムーブされる種類であるかを取得する関数です。
+    /// 整数と共有参照は複製され、構造体は `#[derive(Copy)]` を指定した場合のみ複製されます。
+    /// タプルと配列は、ムーブされる種類の要素を含む場合にムーブされます。
+    /// 型注釈のない仮引数は複製できる値が渡されるとは限らないため、ムーブされるものとします。
+    /// - `structs` - 構造体の定義
+    fn is_move_type(&self, structs: &HashMap<String, Struct>) -> bool {
+        match self {
+            ValueKind::Any | ValueKind::String | ValueKind::Reference(BorrowKind::Mutable, _) | ValueKind::Vec(_) | ValueKind::Box(_) => true,
+            ValueKind::Struct(a) => structs.get(a).is_some_and(|a| a.copy.is_none()),
+            ValueKind::Tuple(a) => a.iter().any(|a| a.is_move_type(structs)),
+            ValueKind::Array(a, _) => a.is_move_type(structs),
+            ValueKind::Unknown | ValueKind::Integer | ValueKind::Reference(BorrowKind::Shared, _) => false,
+        }
+    }
+
+    /// 分岐の合流点で、両方の分岐の値の種類をまとめます。
+    /// 片方が不明であればもう片方を、片方がどの種類でもあり得る値であればその値を、種類が異なれば不明とします。
+    /// - `other` - もう片方の分岐の値の種類
+    fn join(self, other: ValueKind) -> ValueKind {
+        match (self, other) {
+            (ValueKind::Unknown, a) | (a, ValueKind::Unknown) => a,
+            (ValueKind::Any, _) | (_, ValueKind::Any) => ValueKind::Any,
+            (a, b) if a == b => a,
+            _ => ValueKind::Unknown,
+        }
+    }
+}
+
+impl Display for ValueKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ValueKind::Unknown | ValueKind::Any => write!(f, "_"),
+            ValueKind::Integer => write!(f, "i32"),
+            ValueKind::String => write!(f, "String"),
+            ValueKind::Reference(BorrowKind::Shared, a) => write!(f, "&{}", a),
+            ValueKind::Reference(BorrowKind::Mutable, a) => write!(f, "&mut {}", a),
+            ValueKind::Struct(a) => write!(f, "{}", a),
+            ValueKind::Tuple(a) if a.len() == 1 => write!(f, "({},)", a[0]),
+            ValueKind::Tuple(a) => write!(f, "({})", a.iter().map(|a| a.to_string()).collect::<Vec<String>>().join(", ")),
+            ValueKind::Array(a, length) => write!(f, "[{}; {}]", a, length),
+            ValueKind::Vec(a) => write!(f, "Vec<{}>", a),
+            ValueKind::Box(a) => write!(f, "Box<{}>", a),
+        }
+    }
+}
+
+/// 静的解析で扱う値を表す構造体です。
+#[derive(Debug, Clone)]
+struct Value {
+    /// 値の種類
+    kind: ValueKind,
+    /// 値が保持している借用の番号
+    loans: BTreeSet<usize>,
+}
+
+impl Value {
+    fn new(kind: ValueKind) -> Value {
+        Value { kind, loans: BTreeSet::new() }
+    }
+
+    /// 別の値から読み出した値を作成します。整数や文字列のように参照を含みえない値は、元の値の借用を引き継ぎません。
+    /// - `kind` - 読み出した値の種類
+    /// - `loans` - 元の値が保持している借用の番号
+    fn read(kind: ValueKind, loans: BTreeSet<usize>) -> Value {
+        match kind {
+            ValueKind::Integer | ValueKind::String => Value::new(kind),
+            _ => Value { kind, loans },
+        }
+    }
+}
+
+/// 呼び出し側から見た関数の戻り値を表す構造体です。
+#[derive(Debug, Clone)]
+struct Signature {
+    /// 戻り値の種類
+    kind: ValueKind,
+    /// 戻り値が借用を引き継ぐ引数の位置 (戻り値の型が省略されていれば `None`)
+    sources: Option<Vec<usize>>,
+}
+
+/// 変数の所有権の状態を保持する構造体です。
+#[derive(Debug, Clone)]
+struct BindingState {
+    /// 変数名
+    name: String,
+    /// 最後に代入された値の種類
+    kind: ValueKind,
+    /// ムーブされたフィールドの経路ごとの、ムーブされた範囲 (空の経路は変数全体を表します)
+    moves: BTreeMap<Vec<String>, Span>,
+    /// 変数に格納された参照が保持している借用の番号
+    loans: BTreeSet<usize>,
+    /// `mut` が付けられているか
+    mutable: bool,
+    /// 最初に代入された範囲 (代入されていなければ `None`)
+    assigned_at: Option<Span>,
+    /// 現在の位置に至るすべての経路で代入されているか
+    initialized: bool,
+    /// 変数が宣言された範囲
+    declared_at: Span,
+    /// 関数の引数であるか
+    is_parameter: bool,
+}
+
+/// 構文木を評価順に走査し、所有権と借用の規則を静的に検査する構造体です。
+/// 借用は、その借用を保持する変数が生存している間だけ有効であるとみなします。
+/// 文の検査でエラーが見つかった場合はエラーを記録し、次の文から検査を続けます。
+pub struct BorrowChecker<'a> {
+    liveness: &'a Liveness,
+    /// 構造体の定義
+    structs: &'a HashMap<String, Struct>,
+    bindings: HashMap<usize, BindingState>,
+    loans: Vec<Loan>,
+    /// 評価途中の一時的な値が保持している借用の番号
+    in_flight: Vec<BTreeSet<usize>>,
+    /// 検査中のループ
+    loops: Vec<LoopState>,
+    /// 現在の位置に到達しない (`break` や `continue` の後である) か
+    diverged: bool,
+    /// 戻り値が分かっている関数
+    functions: HashMap<String, Signature>,
+    /// 検査中の関数の `return` の値と範囲
+    returns: Vec<(Value, Span)>,
+    /// 値をムーブする式の番号
+    moved: HashSet<usize>,
+    errors: Vec<ParseError>,
+}
+
+/// 検査中のループで、ループを抜ける位置と次の繰り返しへ移る位置の状態を保持する構造体です。
+#[derive(Debug, Default)]
+struct LoopState {
+    /// ループを抜ける位置での変数の状態
+    exits: Vec<HashMap<usize, BindingState>>,
+    /// `break` の値
+    values: Vec<Value>,
+    /// 次の繰り返しへ移る位置での変数の状態
+    continues: Vec<HashMap<usize, BindingState>>,
+}
+
+impl<'a> BorrowChecker<'a> {
+    /// 構文木全体を検査し、値をムーブする式の番号を返します。
+    /// 評価ではムーブされた値を元の場所から取り除き、スコープの終わりで二重に破棄しないようにします。
+    /// - `program` - 検査するブロック
+    /// - `liveness` - 変数の生存区間の解析結果
+    pub fn check(program: &Program, liveness: &'a Liveness) -> Result<HashSet<usize>, Vec<ParseError>> {
+        info!("Start borrow checking...");
+        let mut errors = Vec::new();
+        let mut moved = HashSet::new();
+        // 戻り値の型が書かれた関数は、定義より前の呼び出しでも戻り値が分かる
+        let mut functions: HashMap<String, Signature> = program.functions
+            .values()
+            .filter_map(|a| {
+                let kind = ValueKind::from_type(a.output.as_ref()?);
+                Some((a.name.clone(), Signature { kind, sources: lifetimes::output_sources(a) }))
+            })
+            .collect();
+        // 組み込みの関数 `drop` は引数をムーブし、借用を引き継がない
+        if !program.functions.contains_key(BUILTIN_DROP) {
+            functions.insert(BUILTIN_DROP.to_string(), Signature { kind: ValueKind::Unknown, sources: Some(Vec::new()) });
+        }
+        // 関数は定義された順に検査し、呼び出し先の戻り値の種類が分かっていれば使用する
+        let mut definitions: Vec<&Function> = program.functions.values().collect();
+        definitions.sort_by_key(|a| a.span.start);
+        for a in definitions {
+            let mut checker = BorrowChecker::new(liveness, &program.structs, functions.clone());
+            for b in &a.parameters {
+                checker.declare(b, true);
+            }
+            checker.borrow_parameters(a);
+            let value = checker.check_block(&a.body, None);
+            let span = a.body.tail.as_ref().map_or(a.body.span, |a| a.span);
+            let returns = std::mem::take(&mut checker.returns);
+            let mut kind = ValueKind::Unknown;
+            for (value, span) in returns.into_iter().chain(std::iter::once((value, span))) {
+                let result = checker.check_returned(a, &value, span);
+                checker.recover(result, 0);
+                kind = kind.join(value.kind);
+            }
+            if a.output.is_none() {
+                // 仮引数をそのまま返す関数の戻り値は、呼び出し側で渡した値によるため不明とする
+                if kind == ValueKind::Any {
+                    kind = ValueKind::Unknown;
+                }
+                info!("Function \"{}\" returns {}.", a.name, kind);
+                functions.insert(a.name.clone(), Signature { kind, sources: None });
+            }
+            errors.append(&mut checker.errors);
+            moved.extend(checker.moved);
+        }
+        let mut checker = BorrowChecker::new(liveness, &program.structs, functions);
+        checker.check_block(&program.main, None);
+        errors.append(&mut checker.errors);
+        moved.extend(checker.moved);
+        if errors.is_empty() {
+            Ok(moved)
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// 検査を行う構造体を作成します。
+    /// - `liveness` - 変数の生存区間の解析結果
+    /// - `structs` - 構造体の定義
+    /// - `functions` - 戻り値が分かっている関数
+    fn new(liveness: &'a Liveness, structs: &'a HashMap<String, Struct>, functions: HashMap<String, Signature>) -> BorrowChecker<'a> {
+        BorrowChecker {
+            liveness,
+            structs,
+            bindings: HashMap::new(),
+            loans: Vec::new(),
+            in_flight: Vec::new(),
+            loops: Vec::new(),
+            diverged: false,
+            functions,
+            returns: Vec::new(),
+            moved: HashSet::new(),
+            errors: Vec::new(),
+        }
+    }
+
+    /// 変数を宣言します。引数は呼び出し時に値が代入されているものとします。
+    /// - `declaration` - 変数の宣言
+    /// - `is_parameter` - 関数の引数であるか
+    fn declare(&mut self, declaration: &Declaration, is_parameter: bool) {
+        let state = self.bindings.entry(declaration.binding).or_insert_with(|| BindingState {
+            name: declaration.name.clone(),
+            kind: match &declaration.ty {
+                Some(a) => ValueKind::from_type(a),
+                None if is_parameter => ValueKind::Any,
+                None => ValueKind::Unknown,
+            },
+            moves: BTreeMap::new(),
+            loans: BTreeSet::new(),
+            mutable: false,
+            assigned_at: None,
+            initialized: false,
+            declared_at: declaration.span,
+            is_parameter,
+        });
+        // ループなどで再び宣言された場合は、まだ代入されていない状態に戻す
+        state.mutable = declaration.mutable;
+        state.assigned_at = is_parameter.then_some(declaration.span);
+        state.initialized = is_parameter;
+    }
+
+    /// 文や式の検査結果を受け取り、エラーであれば記録して評価途中の値を破棄します。
+    /// - `result` - 検査結果
+    /// - `depth` - 検査を始める前の評価途中の値の数
+    fn recover<T>(&mut self, result: Result<T, ParseError>, depth: usize) -> Option<T> {
+        match result {
+            Ok(a) => Some(a),
+            Err(e) => {
+                self.errors.push(e);
+                self.in_flight.truncate(depth);
+                None
+            },
+        }
+    }
+
+    /// ブロックを検査します。ブロックの終わりでブロック内の変数は破棄されます。
+    /// - `block` - 検査するブロック
+    /// - `live` - ブロックの直後で生存している変数 (最上位のブロックであれば `None`)
+    fn check_block(&mut self, block: &Block, live: Option<&HashSet<usize>>) -> Value {
+        for a in &block.statements {
+            let depth = self.in_flight.len();
+            let result = match a {
+                Statement::Let(d, initializer) => {
+                    self.declare(d, false);
+                    match initializer {
+                        Some(a) => self.check_expression(a, true).map(|_| ()),
+                        None => Ok(()),
+                    }
+                },
+                Statement::Debug(e) => self.check_expression(e, false).map(|_| ()),
+                Statement::Expression(e) => self.check_expression(e, true).map(|_| ()),
+            };
+            self.recover(result, depth);
+        }
+        let depth = self.in_flight.len();
+        let value = match &block.tail {
+            Some(a) => {
+                let result = self.check_expression(a, true);
+                self.recover(result, depth).unwrap_or(Value::new(ValueKind::Unknown))
+            },
+            None => Value::new(ValueKind::Unknown),
+        };
+        if let Some(live) = live {
+            let result = self.check_dangling(block, live, &value);
+            self.recover(result, depth);
+        }
+        for a in &block.declarations {
+            self.bindings.remove(a);
+        }
+        value
+    }
+
+    /// ブロック内の変数への借用が、ブロックの外で生存している変数やブロックの値に残っていないかを検査します。
+    /// 参照をたどった先の場所への借用は、参照を保持する変数が破棄されても有効であるため対象としません。
+    /// - `block` - 検査するブロック
+    /// - `live` - ブロックの直後で生存している変数
+    /// - `value` - ブロックの値
+    fn check_dangling(&self, block: &Block, live: &HashSet<usize>, value: &Value) -> Result<(), ParseError> {
+        let escaping = self.bindings
+            .iter()
+            .filter(|(binding, _)| live.contains(binding))
+            .flat_map(|(_, state)| state.loans.iter())
+            .chain(value.loans.iter());
+        for a in escaping {
+            let loan = &self.loans[*a];
+            if !loan.reborrowed && block.declarations.contains(&loan.binding) {
+                ret_err!(DanglingReferenceError::new(self.bindings[&loan.binding].name.clone(), loan.at, block.span, block.end))
+            }
+        }
+        Ok(())
+    }
+
+    /// 関数の戻り値が、関数の引数や関数内の変数への借用を保持していないかを検査します。
+    /// 関数内で作成される借用は関数内の変数に対するものであるため、借用を保持していればエラーとします。
+    /// ただし、引数の参照をたどった先の場所への借用は、呼び出し側の値への借用であるため返すことができます。
+    /// - `function` - 検査中の関数
+    /// - `value` - 戻り値
+    /// - `span` - 戻り値となる式の範囲
+    fn check_returned(&self, function: &Function, value: &Value, span: Span) -> Result<(), ParseError> {
+        if let Some(loan) = value.loans.iter().map(|a| &self.loans[*a]).find(|a| !a.reborrowed) {
+            let is_parameter = function.parameters.iter().any(|a| a.binding == loan.binding);
+            ret_err!(ReturnLocalReferenceError::new(loan.name.clone(), is_parameter, loan.at, span))
+        }
+        // 戻り値の型に参照が含まれれば、戻り値の寿命を持つ引数の参照のみを返せる
+        let Some(output) = function.output.as_ref().filter(|a| !a.references().is_empty()) else {
+            return Ok(());
+        };
+        let allowed = lifetimes::output_regions(function).unwrap_or_default();
+        for loan in value.loans.iter().map(|a| &self.loans[*a]) {
+            let Some((region, defined_at)) = loan.region.as_ref().filter(|(a, _)| !allowed.contains(a)) else {
+                continue;
+            };
+            let expected = output.references()
+                .into_iter()
+                .find_map(|(a, _)| a)
+                .map_or(lifetimes::STATIC_LIFETIME.to_string(), |a| a.name.clone());
+            match region {
+                Region::Named(a) if *a == lifetimes::STATIC_LIFETIME => {},
+                Region::Named(a) => ret_err!(LifetimeError::mismatch(expected, a.clone(), false, *defined_at, span)),
+                Region::Elided(_, _) => ret_err!(LifetimeError::mismatch(expected, loan.name.clone(), true, *defined_at, span)),
+            }
+        }
+        Ok(())
+    }
+
+    /// 参照型の引数が受け取った参照に、呼び出し側の借用を表す借用を持たせます。
+    /// 借用には引数の寿命を記録し、戻り値が異なる寿命の参照を返していないかの検査に使用します。
+    /// - `function` - 検査中の関数
+    fn borrow_parameters(&mut self, function: &Function) {
+        for (index, parameter) in function.parameters.iter().enumerate() {
+            let Some(region) = lifetimes::parameter_region(function, index) else {
+                continue;
+            };
+            let Some(TypeKind::Reference(_, kind, _)) = parameter.ty.as_ref().map(|a| &a.kind) else {
+                continue;
+            };
+            let at = parameter.ty.as_ref().map_or(parameter.span, |a| a.span);
+            self.loans.push(Loan { binding: parameter.binding, path: Vec::new(), name: parameter.name.clone(), kind: *kind, at, reborrowed: true, region: Some(region) });
+            let loan = self.loans.len() - 1;
+            self.bindings.get_mut(&parameter.binding).unwrap().loans.insert(loan);
+        }
+    }
+
+    /// 式を検査し、式の値を返します。
+    /// - `expression` - 検査する式
+    /// - `moving` - 変数がそのまま値として使用される場合に、変数をムーブするか
+    fn check_expression(&mut self, expression: &Expression, moving: bool) -> Result<Value, ParseError> {
+        trace!("Checking {} at {}.", expression.id, expression.span);
+        match &expression.kind {
+            ExpressionKind::Integer(_) => Ok(Value::new(ValueKind::Integer)),
+            ExpressionKind::String(_) => Ok(Value::new(ValueKind::String)),
+            ExpressionKind::Variable(v) => self.use_place(v, &[], expression, Self::access(moving)),
+            ExpressionKind::Field(_, _, _) | ExpressionKind::Index(_, _) if Self::place(expression).is_some() => {
+                let (v, path) = Self::place(expression).unwrap();
+                self.check_indices(expression)?;
+                self.use_place(v, &path, expression, Self::access(moving))
+            },
+            ExpressionKind::Field(e, name, _) => {
+                let value = self.check_expression(e, false)?;
+                let (kind, _) = self.field_kind(&value.kind, std::slice::from_ref(name));
+                Ok(Value::read(kind, value.loans))
+            },
+            ExpressionKind::Index(e, index) => {
+                let value = self.check_expression(e, false)?;
+                self.in_flight.push(value.loans.clone());
+                let result = self.check_expression(index, true);
+                self.in_flight.pop();
+                result?;
+                let (kind, _) = self.field_kind(&value.kind, &[INDEX.to_string()]);
+                Ok(Value::read(kind, value.loans))
+            },
+            ExpressionKind::Struct(name, fields) => {
+                let (_, loans) = self.check_elements(fields.iter().map(|a| &a.value))?;
+                Ok(Value { kind: ValueKind::Struct(name.clone()), loans })
+            },
+            ExpressionKind::Tuple(elements) if elements.is_empty() => Ok(Value::new(ValueKind::Unknown)),
+            ExpressionKind::Tuple(elements) => {
+                let (kinds, loans) = self.check_elements(elements.iter())?;
+                Ok(Value { kind: ValueKind::Tuple(kinds), loans })
+            },
+            ExpressionKind::Array(elements) => {
+                let (kinds, loans) = self.check_elements(elements.iter())?;
+                let element = kinds.into_iter().fold(ValueKind::Unknown, ValueKind::join);
+                Ok(Value { kind: ValueKind::Array(Box::new(element), elements.len()), loans })
+            },
+            ExpressionKind::Vec(elements) => {
+                let (kinds, loans) = self.check_elements(elements.iter())?;
+                let element = kinds.into_iter().fold(ValueKind::Unknown, ValueKind::join);
+                Ok(Value { kind: ValueKind::Vec(Box::new(element)), loans })
+            },
+            ExpressionKind::Box(e) => {
+                let value = self.check_expression(e, true)?;
+                Ok(Value { kind: ValueKind::Box(Box::new(value.kind)), loans: value.loans })
+            },
+            ExpressionKind::MethodCall(receiver, name, _, arguments) => {
+                // 引数を先に検査し、受け取り側は引数の評価後に借用する
+                let (_, passed) = self.check_elements(arguments.iter())?;
+                let kind = if matches!(name.as_str(), "len" | "clone") { BorrowKind::Shared } else { BorrowKind::Mutable };
+                self.in_flight.push(passed.clone());
+                let result = self.check_receiver(receiver, kind, expression);
+                self.in_flight.pop();
+                let value = result?;
+                match name.as_str() {
+                    "push" => {
+                        // 格納した値の借用は、受け取り側の変数が保持する
+                        if let Some((v, _)) = Self::place(receiver) {
+                            self.bindings.get_mut(&v.binding).unwrap().loans.extend(passed);
+                        }
+                        Ok(Value::new(ValueKind::Unknown))
+                    },
+                    "pop" => Ok(Value { kind: self.field_kind(&value.kind, &[INDEX.to_string()]).0, loans: value.loans }),
+                    "len" => Ok(Value::new(ValueKind::Integer)),
+                    "clone" => {
+                        // 参照の複製は参照先の値の複製とする
+                        let mut kind = value.kind;
+                        while let ValueKind::Reference(_, a) = kind {
+                            kind = *a;
+                        }
+                        match kind {
+                            ValueKind::Integer | ValueKind::String => Ok(Value::new(kind)),
+                            _ => Ok(Value { kind, loans: value.loans }),
+                        }
+                    },
+                    _ => Ok(Value::new(ValueKind::Unknown)),
+                }
+            },
+            ExpressionKind::Block(b) => Ok(self.check_block(b, Some(self.liveness.live_after(expression.id)))),
+            ExpressionKind::Reference(kind, e) => {
+                let mut value = self.check_place(e)?;
+                // 参照をたどった先の場所 (`&mut *r` や `&r.a`) は参照を保持する変数の一部として借用し、参照が保持している借用も引き継ぐ
+                if let Some(target) = self.target(e) {
+                    let state = &self.bindings[&target.variable.binding];
+                    match target.through {
+                        Some(BorrowKind::Shared) if *kind == BorrowKind::Mutable => ret_err!(ReferenceError::borrow_behind_shared_reference(e.span)),
+                        None if *kind == BorrowKind::Mutable && !state.mutable => {
+                            ret_err!(MutabilityError::borrow_mutable(state.name.clone(), target.name.clone(), expression.span))
+                        },
+                        _ => {},
+                    }
+                    let loan = self.borrow(&target, *kind, expression.span, expression.id)?;
+                    value.loans.insert(loan);
+                }
+                Ok(Value { kind: ValueKind::Reference(*kind, Box::new(value.kind)), loans: value.loans })
+            },
+            ExpressionKind::Dereference(e) => {
+                // `Box` が所有する値を参照外しによってムーブする場合は、`Box` ごとムーブする
+                if let Some((v, path)) = Self::place(e).filter(|_| moving) {
+                    let state = &self.bindings[&v.binding];
+                    if let (ValueKind::Box(inner), through) = self.field_kind(&state.kind, &path) {
+                        if inner.is_move_type(self.structs) {
+                            if let Some(kind) = through {
+                                let place = format!("*{}", Self::place_name(&state.name, &path));
+                                ret_err!(MoveBehindReferenceError::new(Some(place), kind, inner.to_string(), expression.span))
+                            }
+                            self.check_indices(e)?;
+                            let value = self.use_place(v, &path, expression, Access::Move)?;
+                            return Ok(Value { kind: *inner, loans: value.loans });
+                        }
+                    }
+                }
+                let value = self.check_expression(e, false)?;
+                match value.kind {
+                    // 参照の先の値はムーブできない
+                    ValueKind::Reference(kind, a) if moving && a.is_move_type(self.structs) => {
+                        let place = Self::place(e).map(|(v, path)| format!("*{}", Self::place_name(&self.bindings[&v.binding].name, &path)));
+                        ret_err!(MoveBehindReferenceError::new(place, kind, a.to_string(), expression.span))
+                    },
+                    ValueKind::Reference(_, a) | ValueKind::Box(a) => Ok(Value::read(*a, value.loans)),
+                    _ => Ok(Value { kind: ValueKind::Unknown, loans: value.loans }),
+                }
+            },
+            ExpressionKind::Unary(_, e) => {
+                // 単項演算子は整数のみを扱うため、値は複製される
+                self.check_expression(e, false)?;
+                Ok(Value::new(ValueKind::Integer))
+            },
+            ExpressionKind::Binary(op, l, r) => {
+                // 文字列を扱う `+` 以外の演算子は、両辺を参照するか整数として複製するのみでムーブしない
+                let moving = op == "+";
+                let left = self.check_expression(l, moving)?;
+                self.in_flight.push(left.loans);
+                let right = self.check_expression(r, moving);
+                self.in_flight.pop();
+                let right = right?;
+                match (op.as_str(), left.kind, right.kind) {
+                    ("+", ValueKind::String, _) | ("+", _, ValueKind::String) => Ok(Value::new(ValueKind::String)),
+                    _ => Ok(Value::new(ValueKind::Integer)),
+                }
+            },
+            ExpressionKind::Assign(op, target, value) => {
+                let value = match self.check_expression(value, true) {
+                    Ok(a) => a,
+                    Err(e) => {
+                        // 値にエラーがあっても代入は行われたものとし、後の使用でエラーが連鎖しないようにする
+                        if let (ExpressionKind::Variable(v), "=") = (&target.kind, op.as_str()) {
+                            let state = self.bindings.get_mut(&v.binding).unwrap();
+                            state.initialized = true;
+                            state.assigned_at.get_or_insert(target.span);
+                        }
+                        return Err(e);
+                    },
+                };
+                match Self::place(target) {
+                    Some((v, path)) if path.is_empty() => {
+                        let state = self.bindings.get_mut(&v.binding).unwrap();
+                        match state.assigned_at {
+                            // 代入そのものは行ったものとして検査を続け、後の使用でエラーが連鎖しないようにする
+                            Some(first_at) if !state.mutable => {
+                                let error = MutabilityError::assign_twice(state.name.clone(), first_at, state.is_parameter, expression.span);
+                                self.errors.push(error.into());
+                            },
+                            Some(_) => {},
+                            None => state.assigned_at = Some(target.span),
+                        }
+                        if op == "=" {
+                            // ムーブ済みの変数へ代入した場合は、変数を再び使用可能な状態にする
+                            state.kind = value.kind;
+                            state.loans = value.loans;
+                            state.moves.clear();
+                            state.initialized = true;
+                        } else if !state.initialized {
+                            ret_err!(ReferenceError::uninitialized(state.name.clone(), state.declared_at, target.span))
+                        } else if let Some((path, moved_at)) = state.moves.first_key_value() {
+                            let error = MovedValueError::new(state.name.clone(), *moved_at, target.span);
+                            ret_err!(if path.is_empty() { error } else { error.partially() })
+                        }
+                    },
+                    Some((v, path)) => match self.field_kind(&self.bindings[&v.binding].kind, &path).1 {
+                        None => {
+                            self.check_indices(target)?;
+                            self.assign_field(v, &path, op, value, expression.span, target.span)?;
+                        },
+                        Some(BorrowKind::Shared) => ret_err!(ReferenceError::behind_shared_reference(target.span)),
+                        Some(BorrowKind::Mutable) => {
+                            self.in_flight.push(value.loans);
+                            let result = self.check_place(target);
+                            self.in_flight.pop();
+                            result?;
+                        },
+                    },
+                    _ => {
+                        // `Box` の指す値は、`Box` を所有する変数が可変である場合のみ変更できる
+                        if let ExpressionKind::Dereference(e) = &target.kind {
+                            if let Some((v, path)) = Self::place(e) {
+                                let state = &self.bindings[&v.binding];
+                                if let (ValueKind::Box(_), None) = self.field_kind(&state.kind, &path) {
+                                    if !state.mutable {
+                                        let place = format!("*{}", Self::place_name(&state.name, &path));
+                                        ret_err!(MutabilityError::assign_field(state.name.clone(), place, expression.span))
+                                    }
+                                }
+                            }
+                        }
+                        self.in_flight.push(value.loans);
+                        let result = self.check_place(target);
+                        self.in_flight.pop();
+                        result?;
+                    },
+                }
+                self.check_assign(target, expression)?;
+                Ok(Value::new(ValueKind::Unknown))
+            },
+            ExpressionKind::If(condition, then, otherwise) => {
+                self.check_expression(condition, true)?;
+                // 両方の分岐を同じ状態から検査し、合流点で状態をまとめる
+                let before = self.bindings.clone();
+                let then_value = self.check_block(then, Some(self.liveness.live_after(expression.id)));
+                let then_diverged = std::mem::take(&mut self.diverged);
+                let after_then = std::mem::replace(&mut self.bindings, before);
+                let else_value = match otherwise {
+                    Some(a) => self.check_expression(a, true),
+                    None => Ok(Value::new(ValueKind::Unknown)),
+                };
+                // 到達しない分岐の状態はまとめない
+                match (then_diverged, self.diverged) {
+                    (true, false) => {},
+                    (false, true) => {
+                        self.bindings = after_then;
+                        self.diverged = false;
+                    },
+                    _ => self.merge(after_then),
+                }
+                let mut else_value = else_value?;
+                else_value.loans.extend(then_value.loans);
+                Ok(Value { kind: then_value.kind.join(else_value.kind), loans: else_value.loans })
+            },
+            ExpressionKind::While(condition, body) => self.check_loop(expression, Some(condition), body),
+            ExpressionKind::Loop(body) => self.check_loop(expression, None, body),
+            ExpressionKind::Break(value) => {
+                let value = match value {
+                    Some(a) => self.check_expression(a, true)?,
+                    None => Value::new(ValueKind::Unknown),
+                };
+                if let Some(a) = self.loops.last_mut() {
+                    a.exits.push(self.bindings.clone());
+                    a.values.push(value);
+                }
+                self.diverged = true;
+                Ok(Value::new(ValueKind::Unknown))
+            },
+            ExpressionKind::Continue => {
+                if let Some(a) = self.loops.last_mut() {
+                    a.continues.push(self.bindings.clone());
+                }
+                self.diverged = true;
+                Ok(Value::new(ValueKind::Unknown))
+            },
+            ExpressionKind::Call(name, arguments) => {
+                // 引数はムーブまたは借用して渡し、後の引数の評価中も借用は有効である
+                let depth = self.in_flight.len();
+                let mut result = Ok(());
+                for a in arguments {
+                    match self.check_argument(a) {
+                        Ok(value) => self.in_flight.push(value.loans),
+                        Err(e) => {
+                            result = Err(e);
+                            break;
+                        },
+                    }
+                }
+                let passed = self.in_flight.split_off(depth);
+                result?;
+                let signature = self.functions.get(name).cloned().unwrap_or(Signature { kind: ValueKind::Unknown, sources: None });
+                let loans = match signature.sources {
+                    // 戻り値の寿命を持つ引数の借用のみを引き継ぐ
+                    Some(sources) => sources.iter().flat_map(|a| passed[*a].iter().copied()).collect(),
+                    // 参照を返しうる関数の戻り値は、引数の借用を引き継ぐ
+                    None if matches!(signature.kind, ValueKind::Reference(_, _) | ValueKind::Unknown) => passed.into_iter().flatten().collect(),
+                    None => BTreeSet::new(),
+                };
+                Ok(Value { kind: signature.kind, loans })
+            },
+            ExpressionKind::Return(value) => {
+                let (value, span) = match value {
+                    Some(a) => (self.check_expression(a, true)?, a.span),
+                    None => (Value::new(ValueKind::Unknown), expression.span),
+                };
+                self.returns.push((value, span));
+                self.diverged = true;
+                Ok(Value::new(ValueKind::Unknown))
+            },
+        }
+    }
+
+    /// 関数の引数を検査し、引数の値を返します。
+    /// 可変参照を保持する場所は、ムーブせずに `&mut *a` と同じく参照先を再借用して渡します。
+    /// - `argument` - 引数の式
+    fn check_argument(&mut self, argument: &Expression) -> Result<Value, ParseError> {
+        let Some(mut target) = self.target(argument) else {
+            return self.check_expression(argument, true);
+        };
+        let (kind, _) = self.field_kind(&self.bindings[&target.variable.binding].kind, &target.path);
+        if !matches!(kind, ValueKind::Reference(BorrowKind::Mutable, _)) {
+            return self.check_expression(argument, true);
+        }
+        let mut value = self.check_place(argument)?;
+        target.through.get_or_insert(BorrowKind::Mutable);
+        target.name = format!("*{}", target.name);
+        let loan = self.borrow(&target, BorrowKind::Mutable, argument.span, argument.id)?;
+        value.loans.insert(loan);
+        Ok(value)
+    }
+
+    /// ループを検査し、ループの値を返します。
+    /// 1 回目の繰り返しの後の状態をループの前の状態とまとめて 2 回目の繰り返しを検査し、
+    /// 前の繰り返しでムーブされた値の使用などを見つけます。
+    /// - `expression` - ループを表す式
+    /// - `condition` - `while` の条件式 (`loop` であれば `None`)
+    /// - `body` - ループの本体
+    fn check_loop(&mut self, expression: &Expression, condition: Option<&Expression>, body: &Block) -> Result<Value, ParseError> {
+        let entry = self.bindings.clone();
+        self.loops.push(LoopState::default());
+        let result = self.check_iteration(expression, condition, body);
+        let continues = std::mem::take(&mut self.loops.last_mut().unwrap().continues);
+        if result.is_ok() && !continues.is_empty() {
+            // 2 回目の繰り返しで新たに見つかったエラーのみを記録する
+            self.bindings = entry.clone();
+            for a in continues {
+                self.merge(a);
+            }
+            let reported = std::mem::take(&mut self.errors);
+            let second = self.check_iteration(expression, condition, body);
+            let found = std::mem::replace(&mut self.errors, reported);
+            for a in found.into_iter().chain(second.err()) {
+                let span = a.diagnostic().primary.map(|a| a.span);
+                if !self.errors.iter().any(|b| b.code() == a.code() && b.diagnostic().primary.map(|b| b.span) == span) {
+                    self.errors.push(Self::in_previous_iteration(a, expression.span));
+                }
+            }
+        }
+        let state = self.loops.pop().unwrap();
+        result?;
+        // ループを抜ける位置の状態をまとめる
+        let mut exits = state.exits.into_iter();
+        match exits.next() {
+            Some(a) => {
+                self.bindings = a;
+                for a in exits {
+                    self.merge(a);
+                }
+                self.bindings.retain(|a, _| entry.contains_key(a));
+                self.diverged = false;
+            },
+            None => self.diverged = true,
+        }
+        let mut values = state.values.into_iter();
+        let first = values.next().unwrap_or(Value::new(ValueKind::Unknown));
+        Ok(values.fold(first, |mut a, b| {
+            a.loans.extend(b.loans);
+            Value { kind: a.kind.join(b.kind), loans: a.loans }
+        }))
+    }
+
+    /// ループの繰り返しを 1 回分検査します。
+    /// - `expression` - ループを表す式
+    /// - `condition` - `while` の条件式 (`loop` であれば `None`)
+    /// - `body` - ループの本体
+    fn check_iteration(&mut self, expression: &Expression, condition: Option<&Expression>, body: &Block) -> Result<(), ParseError> {
+        if let Some(a) = condition {
+            self.check_expression(a, true)?;
+            // 条件を満たさなければループを抜ける
+            let state = self.bindings.clone();
+            self.loops.last_mut().unwrap().exits.push(state);
+        }
+        self.check_block(body, Some(self.liveness.live_across(expression.id)));
+        if !std::mem::take(&mut self.diverged) {
+            let state = self.bindings.clone();
+            self.loops.last_mut().unwrap().continues.push(state);
+        }
+        Ok(())
+    }
+
+    /// ムーブ済みの値の使用が、ループの前の繰り返しでのムーブによるものであれば、その旨をエラーに設定します。
+    /// - `error` - 2 回目の繰り返しの検査で見つかったエラー
+    /// - `span` - ループの範囲
+    fn in_previous_iteration(error: ParseError, span: Span) -> ParseError {
+        match error {
+            ParseError::MovedValue(a) if a.moved_at().start >= a.used_at().start && a.moved_at().start >= span.start => {
+                ParseError::MovedValue(a.in_previous_iteration())
+            },
+            a => a,
+        }
+    }
+
+    /// 別の分岐で検査した変数の状態を現在の状態にまとめます。
+    /// いずれかの分岐でムーブまたは代入された変数はムーブ済みまたは代入済みとし、
+    /// すべての分岐で代入された変数のみを初期化済みとします。借用はすべての分岐のものを保持します。
+    /// - `other` - 別の分岐の終わりでの変数の状態
+    fn merge(&mut self, other: HashMap<usize, BindingState>) {
+        for (binding, state) in other {
+            if let Some(current) = self.bindings.get_mut(&binding) {
+                for (path, at) in state.moves {
+                    current.moves.entry(path).or_insert(at);
+                }
+                current.assigned_at = current.assigned_at.or(state.assigned_at);
+                current.initialized &= state.initialized;
+                current.loans.extend(state.loans);
+                current.kind = std::mem::replace(&mut current.kind, ValueKind::Unknown).join(state.kind);
+            }
+        }
+    }
+
+    /// 変数とフィールドの経路で表される場所を求めます。参照外しを含むなど、変数から始まる場所でなければ `None` を返します。
+    /// 配列の要素は、添字によらず [`INDEX`] で表します。
+    /// - `expression` - 場所を表す式
+    fn place(expression: &Expression) -> Option<(&Variable, Vec<String>)> {
+        match &expression.kind {
+            ExpressionKind::Variable(v) => Some((v, Vec::new())),
+            ExpressionKind::Field(e, name, _) => {
+                let (v, mut path) = Self::place(e)?;
+                path.push(name.clone());
+                Some((v, path))
+            },
+            ExpressionKind::Index(e, _) => {
+                let (v, mut path) = Self::place(e)?;
+                path.push(INDEX.to_string());
+                Some((v, path))
+            },
+            _ => None,
+        }
+    }
+
+    /// 借用や代入の対象となる場所を求めます。変数から始まり、フィールド、添字、参照外しのみからなる式でなければ `None` を返します。
+    /// 参照外しをたどった場所は、参照や `Box` を保持する場所の一部とみなします。
+    /// - `expression` - 場所を表す式
+    fn target<'e>(&self, expression: &'e Expression) -> Option<Target<'e>> {
+        let (e, element) = match &expression.kind {
+            ExpressionKind::Variable(v) => return Some(Target { variable: v, path: Vec::new(), name: v.name.clone(), through: None }),
+            ExpressionKind::Dereference(e) => {
+                let mut target = self.target(e)?;
+                if let ValueKind::Reference(a, _) = self.field_kind(&self.bindings[&target.variable.binding].kind, &target.path).0 {
+                    target.through.get_or_insert(a);
+                }
+                target.name = format!("*{}", target.name);
+                return Some(target);
+            },
+            ExpressionKind::Field(e, name, _) => (e, name.clone()),
+            ExpressionKind::Index(e, _) => (e, INDEX.to_string()),
+            _ => return None,
+        };
+        let mut target = self.target(e)?;
+        // 参照外しのフィールドは `(*a).b` の形式で表す
+        if let ExpressionKind::Dereference(_) = e.kind {
+            target.name = format!("({})", target.name);
+        }
+        target.name = Self::place_name(&target.name, std::slice::from_ref(&element));
+        target.path.push(element);
+        let (_, through) = self.field_kind(&self.bindings[&target.variable.binding].kind, &target.path);
+        target.through = target.through.or(through);
+        Some(target)
+    }
+
+    /// 場所を表す式に含まれる添字を、外側の値から順に検査します。
+    /// - `expression` - 場所を表す式
+    fn check_indices(&mut self, expression: &Expression) -> Result<(), ParseError> {
+        match &expression.kind {
+            ExpressionKind::Field(e, _, _) => self.check_indices(e),
+            ExpressionKind::Index(e, index) => {
+                self.check_indices(e)?;
+                self.check_expression(index, true).map(|_| ())
+            },
+            _ => Ok(()),
+        }
+    }
+
+    /// 場所を `a.b` や `a[_]` の形式の文字列で表します。
+    /// - `name` - 変数名
+    /// - `path` - フィールドの経路
+    fn place_name(name: &str, path: &[String]) -> String {
+        path.iter().fold(name.to_string(), |name, a| match a.as_str() {
+            INDEX => format!("{}{}", name, a),
+            _ => format!("{}.{}", name, a),
+        })
+    }
+
+    /// 式をソースコード上の表記で表します。変数、整数、フィールドと添字のみからなる式でなければ `None` を返します。
+    /// - `expression` - 表記を求める式
+    fn source_text(expression: &Expression) -> Option<String> {
+        match &expression.kind {
+            ExpressionKind::Variable(v) => Some(v.name.clone()),
+            ExpressionKind::Integer(i) => Some(i.to_string()),
+            ExpressionKind::Field(e, name, _) => Some(format!("{}.{}", Self::source_text(e)?, name)),
+            ExpressionKind::Index(e, index) => Some(format!("{}[{}]", Self::source_text(e)?, Self::source_text(index)?)),
+            _ => None,
+        }
+    }
+
+    /// 二つのフィールドの経路の片方がもう片方を含むかを取得します。
+    /// - `a` - 一つ目の経路
+    /// - `b` - 二つ目の経路
+    fn overlaps(a: &[String], b: &[String]) -> bool {
+        a.iter().zip(b).all(|(a, b)| a == b)
+    }
+
+    /// フィールドの経路をたどった先の値の種類を求めます。参照のフィールドは参照先のフィールドとします。
+    /// 経路の途中で参照をたどった場合は、最初にたどった参照の種類も返します。
+    /// - `kind` - 経路の始まりの値の種類
+    /// - `path` - フィールドの経路
+    fn field_kind(&self, kind: &ValueKind, path: &[String]) -> (ValueKind, Option<BorrowKind>) {
+        let mut kind = kind.clone();
+        let mut through = None;
+        for name in path {
+            while let ValueKind::Reference(a, inner) = kind {
+                through.get_or_insert(a);
+                kind = *inner;
+            }
+            kind = match &kind {
+                ValueKind::Struct(a) => self.structs
+                    .get(a)
+                    .and_then(|b| b.field(name))
+                    .map_or(ValueKind::Unknown, |(_, b)| ValueKind::from_type(&b.ty)),
+                ValueKind::Tuple(a) => name.parse::<usize>().ok().and_then(|b| a.get(b)).cloned().unwrap_or(ValueKind::Unknown),
+                ValueKind::Array(a, _) | ValueKind::Vec(a) if name == INDEX => (**a).clone(),
+                _ => ValueKind::Unknown,
+            };
+        }
+        (kind, through)
+    }
+
+    /// 値をムーブするかどうかから、場所の使われ方を求めます。
+    /// - `moving` - 変数がそのまま値として使用される場合に、変数をムーブするか
+    fn access(moving: bool) -> Access {
+        if moving {
+            Access::Move
+        } else {
+            Access::Read
+        }
+    }
+
+    /// 変数またはそのフィールドを使用します。
+    /// 使用する場所がムーブ済みであるか、一部のフィールドがムーブ済みであればエラーを返します。
+    /// 値を読み出す場合は、読み出す場所への可変の借用が有効でないことも確認します。
+    /// - `variable` - 使用する変数
+    /// - `path` - 使用するフィールドの経路 (変数全体であれば空)
+    /// - `expression` - 使用した式
+    /// - `access` - 場所の使われ方
+    fn use_place(&mut self, variable: &Variable, path: &[String], expression: &Expression, access: Access) -> Result<Value, ParseError> {
+        let span = expression.span;
+        let (kind, through) = self.field_kind(&self.bindings[&variable.binding].kind, path);
+        let state = &self.bindings[&variable.binding];
+        if !state.initialized {
+            ret_err!(ReferenceError::uninitialized(state.name.clone(), state.declared_at, span))
+        }
+        for (moved, moved_at) in &state.moves {
+            if Self::overlaps(moved, path) {
+                if moved.len() <= path.len() {
+                    let error = MovedValueError::new(Self::place_name(&state.name, moved), *moved_at, span);
+                    ret_err!(self.suggest_clone(error, state, moved))
+                }
+                let error = MovedValueError::new(Self::place_name(&state.name, path), *moved_at, span).partially();
+                ret_err!(self.suggest_clone(error, state, moved))
+            }
+        }
+        if access == Access::Move && kind.is_move_type(self.structs) {
+            // 参照をたどった先の値はムーブできない
+            if let Some(reference) = through {
+                ret_err!(MoveBehindReferenceError::new(Some(Self::place_name(&state.name, path)), reference, kind.to_string(), span))
+            }
+            // 配列の要素は一部だけをムーブできない
+            if let Some(i) = path.iter().position(|a| a == INDEX) {
+                let (array, _) = self.field_kind(&state.kind, &path[..i]);
+                let place = Self::place_name(&state.name, path);
+                let error = match array {
+                    ValueKind::Vec(_) => MoveOutError::index(place, array.to_string(), kind.to_string(), span),
+                    _ => MoveOutError::array(place, array.to_string(), kind.to_string(), span),
+                };
+                match Self::source_text(expression) {
+                    Some(a) => ret_err!(error.suggest_borrow(a)),
+                    None => ret_err!(error),
+                }
+            }
+            // 借用されている値はムーブできない
+            if let Some(loan) = self.conflicting_loan(variable.binding, path, BorrowKind::Mutable, expression.id) {
+                ret_err!(BorrowedMoveError::new(Self::place_name(&state.name, path), loan.name.clone(), loan.at, span))
+            }
+            let state = self.bindings.get_mut(&variable.binding).unwrap();
+            info!("Variable \"{}\" was moved at {}.", Self::place_name(&state.name, path), span);
+            state.moves.insert(path.to_vec(), span);
+            self.moved.insert(expression.id);
+        } else if access != Access::Place {
+            // 可変として借用されている値は、借用が有効な間は読み出せない
+            if let Some(loan) = self.conflicting_loan(variable.binding, path, BorrowKind::Shared, expression.id) {
+                ret_err!(BorrowedAccessError::used(Self::place_name(&state.name, path), loan.name.clone(), loan.at, span))
+            }
+        }
+        let loans = self.bindings[&variable.binding].loans.clone();
+        match access {
+            Access::Place => Ok(Value { kind, loans }),
+            Access::Move | Access::Read => Ok(Value::read(kind, loans)),
+        }
+    }
+
+    /// ムーブされた値が `.clone()` で複製できる種類であれば、ムーブする代わりに複製することを提案します。
+    /// - `error` - ムーブ済みの値を使用したエラー
+    /// - `state` - ムーブされた変数の状態
+    /// - `moved` - ムーブされたフィールドの経路
+    fn suggest_clone(&self, error: MovedValueError, state: &BindingState, moved: &[String]) -> MovedValueError {
+        match self.field_kind(&state.kind, moved).0 {
+            ValueKind::String | ValueKind::Vec(_) | ValueKind::Box(_) | ValueKind::Struct(_) => error.suggest_clone(Self::place_name(&state.name, moved)),
+            _ => error,
+        }
+    }
+
+    /// 変数のフィールドへ代入します。
+    /// 代入によってフィールドは再び使用可能な状態になりますが、ムーブ済みの値の一部へは代入できません。
+    /// - `variable` - 代入先の変数
+    /// - `path` - 代入先のフィールドの経路
+    /// - `op` - 代入演算子
+    /// - `value` - 代入する値
+    /// - `span` - 代入式の範囲
+    /// - `target` - 代入先の式の範囲
+    fn assign_field(&mut self, variable: &Variable, path: &[String], op: &str, value: Value, span: Span, target: Span) -> Result<(), ParseError> {
+        let state = self.bindings.get_mut(&variable.binding).unwrap();
+        if !state.initialized {
+            ret_err!(ReferenceError::uninitialized(state.name.clone(), state.declared_at, target))
+        }
+        if !state.mutable {
+            ret_err!(MutabilityError::assign_field(state.name.clone(), Self::place_name(&state.name, path), span))
+        }
+        for (moved, moved_at) in &state.moves {
+            // 複合代入はフィールドの値を使用する
+            if Self::overlaps(moved, path) && (moved.len() < path.len() || op != "=") {
+                ret_err!(MovedValueError::new(Self::place_name(&state.name, moved), *moved_at, target))
+            }
+        }
+        if op == "=" {
+            state.moves.retain(|a, _| !a.starts_with(path));
+        }
+        state.loans.extend(value.loans);
+        Ok(())
+    }
+
+    /// メソッドの受け取り側の式を検査します。
+    /// 変数またはそのフィールドは、参照をたどった先の場所も含めて、メソッドの呼び出しの間だけ借用します。
+    /// - `receiver` - 受け取り側の式
+    /// - `kind` - 受け取り側を借用する種類
+    /// - `expression` - メソッド呼び出しの式
+    fn check_receiver(&mut self, receiver: &Expression, kind: BorrowKind, expression: &Expression) -> Result<Value, ParseError> {
+        let value = self.check_place(receiver)?;
+        let mut target = self.target(receiver);
+        // 参照の値のメソッドは参照先のメソッドとする
+        let through = match &value.kind {
+            ValueKind::Reference(a, _) => {
+                if let Some(target) = &mut target {
+                    target.through.get_or_insert(*a);
+                    target.name = format!("*{}", target.name);
+                }
+                target.as_ref().and_then(|a| a.through).or(Some(*a))
+            },
+            _ => target.as_ref().and_then(|a| a.through),
+        };
+        if through == Some(BorrowKind::Shared) && kind == BorrowKind::Mutable {
+            ret_err!(ReferenceError::borrow_behind_shared_reference(receiver.span))
+        }
+        if let Some(target) = target {
+            let state = &self.bindings[&target.variable.binding];
+            if through.is_none() && kind == BorrowKind::Mutable && !state.mutable {
+                ret_err!(MutabilityError::borrow_mutable(state.name.clone(), target.name.clone(), receiver.span))
+            }
+            self.borrow(&target, kind, expression.span, expression.id)?;
+        }
+        Ok(value)
+    }
+
+    /// 借用や代入、メソッドの受け取り側として使用する場所を、値を読み出さずに検査し、場所にある値を返します。
+    /// - `expression` - 場所を表す式
+    fn check_place(&mut self, expression: &Expression) -> Result<Value, ParseError> {
+        match &expression.kind {
+            ExpressionKind::Variable(v) => self.use_place(v, &[], expression, Access::Place),
+            ExpressionKind::Field(_, _, _) | ExpressionKind::Index(_, _) if Self::place(expression).is_some() => {
+                let (v, path) = Self::place(expression).unwrap();
+                self.check_indices(expression)?;
+                self.use_place(v, &path, expression, Access::Place)
+            },
+            ExpressionKind::Dereference(e) => {
+                let value = self.check_place(e)?;
+                match value.kind {
+                    ValueKind::Reference(_, a) | ValueKind::Box(a) => Ok(Value { kind: *a, loans: value.loans }),
+                    _ => Ok(Value { kind: ValueKind::Unknown, loans: value.loans }),
+                }
+            },
+            _ => self.check_expression(expression, false),
+        }
+    }
+
+    /// 代入先の場所が有効な借用と競合しないかを検査します。複合代入も、代入先を可変として借用するものとします。
+    /// - `target` - 代入先の式
+    /// - `expression` - 代入式
+    fn check_assign(&self, target: &Expression, expression: &Expression) -> Result<(), ParseError> {
+        let Some(place) = self.target(target) else {
+            return Ok(());
+        };
+        if let Some(loan) = self.conflicting_loan(place.variable.binding, &place.path, BorrowKind::Mutable, expression.id) {
+            ret_err!(BorrowedAccessError::assigned(place.name, loan.name.clone(), loan.at, expression.span))
+        }
+        Ok(())
+    }
+
+    /// 構造体式、タプル式、配列式の要素やメソッドの引数を検査し、要素の値の種類と、要素が保持している借用をまとめて返します。
+    /// 要素の値はムーブして格納し、後の要素の評価中も借用は有効です。
+    /// - `elements` - 書かれた順の要素
+    fn check_elements<'b>(&mut self, elements: impl Iterator<Item = &'b Expression>) -> Result<(Vec<ValueKind>, BTreeSet<usize>), ParseError> {
+        let depth = self.in_flight.len();
+        let mut kinds = Vec::new();
+        let mut result = Ok(());
+        for a in elements {
+            match self.check_expression(a, true) {
+                Ok(value) => {
+                    kinds.push(value.kind);
+                    self.in_flight.push(value.loans);
+                },
+                Err(e) => {
+                    result = Err(e);
+                    break;
+                },
+            }
+        }
+        let loans = self.in_flight.split_off(depth).into_iter().flatten().collect();
+        result?;
+        Ok((kinds, loans))
+    }
+
+    /// 変数またはそのフィールドへの有効な借用のうち、新しい借用と競合する借用を探します。
+    /// 有効な借用は、式の直後で生存している変数と評価途中の値が保持している借用です。
+    /// - `binding` - 借用する変数を識別する番号
+    /// - `path` - 借用するフィールドの経路 (変数全体であれば空)
+    /// - `kind` - 新しい借用の種類 (ムーブは可変の借用と同じくすべての借用と競合します)
+    /// - `id` - 借用を作成する式の番号
+    fn conflicting_loan(&self, binding: usize, path: &[String], kind: BorrowKind, id: usize) -> Option<&Loan> {
+        self.bindings
+            .iter()
+            .filter(|(a, _)| self.liveness.is_live_after(id, **a))
+            .flat_map(|(_, state)| state.loans.iter())
+            .chain(self.in_flight.iter().flatten())
+            .map(|a| &self.loans[*a])
+            .filter(|a| a.region.is_none())
+            .find(|a| a.binding == binding && Self::overlaps(&a.path, path) && (a.kind == BorrowKind::Mutable || kind == BorrowKind::Mutable))
+    }
+
+    /// 変数またはそのフィールドを借用します。有効な借用と競合する場合はエラーを返します。
+    /// 異なるフィールドへの借用は競合しません。
+    /// - `target` - 借用する場所
+    /// - `kind` - 借用の種類
+    /// - `at` - 借用が作成された範囲
+    /// - `id` - 借用を作成する式の番号
+    fn borrow(&mut self, target: &Target, kind: BorrowKind, at: Span, id: usize) -> Result<usize, ParseError> {
+        let binding = target.variable.binding;
+        if let Some(loan) = self.conflicting_loan(binding, &target.path, kind, id) {
+            ret_err!(BorrowError::new(target.name.clone(), loan.kind, loan.at, kind, at))
+        }
+        info!("Variable \"{}\" was borrowed as {:?} at {}.", target.name, kind, at);
+        let loan = Loan { binding, path: target.path.clone(), name: target.name.clone(), kind, at, reborrowed: target.through.is_some(), region: None };
+        self.loans.push(loan);
+        Ok(self.loans.len() - 1)
+    }
+}
@@ -0,0 +1,208 @@
+use std::fmt::Write;
+
+use unicode_width::UnicodeWidthChar;
+
+use super::token::Span;
+
+/// ソースコード上の範囲に付けるラベルです。
+#[derive(Debug, Clone)]
+pub struct Label {
+    /// ラベルを付ける範囲
+    pub span: Span,
+    /// ラベルの文言 (空の場合は下線のみ表示します)
+    pub message: String,
+}
+
+impl Label {
+    pub fn new(span: Span, message: impl Into<String>) -> Label {
+        Label { span, message: message.into() }
+    }
+}
+
+/// rustc と同様の形式で表示するための診断情報です。
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    /// エラーコード
+    pub code: Option<&'static str>,
+    /// エラーの概要
+    pub message: String,
+    /// エラーの主な原因となった位置のラベル (`^` で下線を引きます)
+    pub primary: Option<Label>,
+    /// 補足するラベル (`-` で下線を引きます)
+    pub secondary: Vec<Label>,
+    /// ソースコードの抜粋の後に表示する助言
+    pub help: Vec<String>,
+}
+
+impl Diagnostic {
+    /// 位置情報のない診断情報を作成します。
+    /// - `message` - エラーの概要
+    pub fn new(message: impl Into<String>) -> Diagnostic {
+        Diagnostic { code: None, message: message.into(), primary: None, secondary: Vec::new(), help: Vec::new() }
+    }
+
+    /// エラーコードを設定します。
+    /// - `code` - エラーコード
+    pub fn with_code(mut self, code: &'static str) -> Diagnostic {
+        self.code = Some(code);
+        self
+    }
+
+    /// 主なラベルを設定します。
+    /// - `span` - ラベルを付ける範囲
+    /// - `message` - ラベルの文言
+    pub fn with_primary(mut self, span: Span, message: impl Into<String>) -> Diagnostic {
+        self.primary = Some(Label::new(span, message));
+        self
+    }
+
+    /// 補足するラベルを追加します。
+    /// - `span` - ラベルを付ける範囲
+    /// - `message` - ラベルの文言
+    pub fn with_secondary(mut self, span: Span, message: impl Into<String>) -> Diagnostic {
+        self.secondary.push(Label::new(span, message));
+        self
+    }
+
+    /// 助言を追加します。
+    /// - `message` - 助言の文言
+    pub fn with_help(mut self, message: impl Into<String>) -> Diagnostic {
+        self.help.push(message.into());
+        self
+    }
+
+    /// 診断情報をソースコードの抜粋と共に文字列にします。
+    /// - `file_name` - ソースコードのファイル名
+    /// - `source` - ソースコード全体
+    pub fn render(&self, file_name: &str, source: &str) -> String {
+        let mut out = String::new();
+        match self.code {
+            Some(a) => _ = writeln!(out, "error[{}]: {}", a, self.message),
+            None => _ = writeln!(out, "error: {}", self.message),
+        }
+        let Some(primary) = &self.primary else {
+            for a in &self.help {
+                _ = writeln!(out, "  = help: {}", a);
+            }
+            return out;
+        };
+
+        // 行番号順に並べたラベルと、主なラベルかどうか
+        let mut labels: Vec<(&Label, bool)> = std::iter::once((primary, true))
+            .chain(self.secondary.iter().map(|a| (a, false)))
+            .collect();
+        labels.sort_by_key(|(a, _)| (a.span.line, a.span.column));
+        let width = labels.iter().map(|(a, _)| a.span.line).max().unwrap_or(1).to_string().len();
+        let pad = " ".repeat(width);
+        let lines: Vec<&str> = source.lines().collect();
+
+        _ = writeln!(out, "{}--> {}:{}:{}", pad, file_name, primary.span.line, primary.span.column);
+        _ = writeln!(out, "{} |", pad);
+        let mut previous: Option<usize> = None;
+        for group in labels.chunk_by(|(a, _), (b, _)| a.span.line == b.span.line) {
+            let line = group[0].0.span.line;
+            let text = lines.get(line.wrapping_sub(1)).copied().unwrap_or("");
+            if previous.is_some_and(|a| a + 1 < line) {
+                _ = writeln!(out, "...");
+            }
+            _ = writeln!(out, "{:>width$} | {}", line, text);
+            previous = Some(line);
+            for row in label_rows(group, text, source) {
+                _ = writeln!(out, "{} | {}", pad, row);
+            }
+        }
+        if !self.help.is_empty() {
+            _ = writeln!(out, "{} |", pad);
+            for a in &self.help {
+                _ = writeln!(out, "{} = help: {}", pad, a);
+            }
+        }
+        out
+    }
+}
+
+/// 同じ行に付けるラベルを、rustc と同様に 1 行の下線とその下に垂らした文言の行にします。
+/// 最も右にあるラベルの文言は下線の後ろに続けて表示し、それ以外の文言は右のラベルから順に下の行へ表示します。
+/// 下線の位置は、全角文字を 2 桁とする表示上の桁で揃えます。
+/// - `labels` - 列番号順に並べた同じ行のラベルと、主なラベルかどうか
+/// - `text` - ラベルを付ける行
+/// - `source` - ソースコード全体
+fn label_rows(labels: &[(&Label, bool)], text: &str, source: &str) -> Vec<String> {
+    // 行の各文字を表示幅の分だけの桁に広げ、タブはそのまま残す
+    let cells: Vec<char> = text
+        .chars()
+        .flat_map(|a| std::iter::repeat_n(if a == '\t' { '\t' } else { ' ' }, display_width(a)))
+        .collect();
+    // 範囲の開始位置と終了位置 (表示上の桁)
+    let ranges: Vec<(usize, usize)> = labels
+        .iter()
+        .map(|(a, _)| {
+            let start = text.chars().take(a.span.column.saturating_sub(1)).map(display_width).sum();
+            (start, start + underline_length(a.span, source))
+        })
+        .collect();
+    // 行頭からの空白はタブをそのまま残し、下線の位置を揃える
+    let blank = |width: usize| -> Vec<char> {
+        (0..width).map(|i| cells.get(i).copied().unwrap_or(' ')).collect()
+    };
+    let finish = |row: Vec<char>| row.into_iter().collect::<String>().trim_end().to_string();
+
+    let mut underline = blank(ranges.iter().map(|a| a.1).max().unwrap_or(0));
+    // 主なラベルの下線を補足するラベルの下線より優先する
+    for is_primary in [false, true] {
+        for ((_, primary), (start, end)) in labels.iter().zip(&ranges) {
+            if *primary == is_primary {
+                underline[*start..*end].fill(if is_primary { '^' } else { '-' });
+            }
+        }
+    }
+
+    // 下に垂らして文言を表示するラベル
+    let mut hanging: Vec<usize> = (0..labels.len()).filter(|i| !labels[*i].0.message.is_empty()).collect();
+    let last = labels.len() - 1;
+    if hanging.last() == Some(&last) && ranges[..last].iter().all(|a| a.1 <= ranges[last].0) {
+        hanging.pop();
+        underline.push(' ');
+        underline.extend(labels[last].0.message.chars());
+    }
+    let mut rows = vec![finish(underline)];
+    if hanging.is_empty() {
+        return rows;
+    }
+    let width = ranges.iter().map(|a| a.1).max().unwrap_or(0);
+    let connectors = |pending: &[usize]| -> Vec<char> {
+        let mut row = blank(width);
+        for i in pending {
+            row[ranges[*i].0] = '|';
+        }
+        row
+    };
+    rows.push(finish(connectors(&hanging)));
+    while let Some(i) = hanging.pop() {
+        let mut row = connectors(&hanging);
+        row.truncate(ranges[i].0);
+        row.extend(labels[i].0.message.chars());
+        rows.push(finish(row));
+    }
+    rows
+}
+
+/// 下線の長さを表示上の桁数で返します。複数行にわたる範囲は最初の行の終わりまで下線を引きます。
+/// - `span` - 下線を引く範囲
+/// - `source` - ソースコード全体
+fn underline_length(span: Span, source: &str) -> usize {
+    source
+        .get(span.start..span.end)
+        .map(|a| a.split('\n').next().unwrap_or("").trim_end_matches('\r').chars().map(display_width).sum())
+        .unwrap_or(0)
+        .max(1)
+}
+
+/// 文字の表示上の桁数を返します。全角文字は 2 桁とし、タブは下線の行にそのまま出力するため 1 桁とします。
+/// - `c` - 桁数を求める文字
+fn display_width(c: char) -> usize {
+    match c {
+        '\t' => 1,
+        a => a.width().unwrap_or(0),
+    }
+}
@@ -1,54 +1,119 @@
 use std::{fmt::Display, error::Error};
 
+use super::borrow::BorrowKind;
+use super::diagnostic::{Diagnostic, Label};
+use super::token::Span;
+
 /// 未定義の変数を参照しようとしたときのエラーです。
 #[derive(Debug)]
 pub struct VariableNotFoundError {
     name: String,
+    span: Span,
+    kind: NameKind,
+}
+
+/// 見つからなかった名前の種類を定義します。
+#[derive(Debug, Clone, Copy)]
+enum NameKind {
+    Variable,
+    Function,
+    Type,
 }
 
 impl VariableNotFoundError {
-    pub fn new(name: String) -> VariableNotFoundError {
-        VariableNotFoundError { name }
+    pub fn new(name: String, span: Span) -> VariableNotFoundError {
+        VariableNotFoundError { name, span, kind: NameKind::Variable }
+    }
+
+    /// 未定義の関数を呼び出そうとしたときのエラーを作成します。
+    pub fn function(name: String, span: Span) -> VariableNotFoundError {
+        VariableNotFoundError { name, span, kind: NameKind::Function }
+    }
+
+    /// 未定義の型を型注釈に使用したときのエラーを作成します。
+    pub fn type_name(name: String, span: Span) -> VariableNotFoundError {
+        VariableNotFoundError { name, span, kind: NameKind::Type }
+    }
+
+    pub fn diagnostic(&self) -> Diagnostic {
+        let kind = match self.kind {
+            NameKind::Variable => "value",
+            NameKind::Function => "function",
+            NameKind::Type => "type",
+        };
+        Diagnostic::new(format!("cannot find {} `{}` in this scope", kind, self.name))
+            .with_primary(self.span, "not found in this scope")
     }
 }
 
 impl Display for VariableNotFoundError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "Variable \"{}\" was not found.", self.name)
+        let kind = match self.kind {
+            NameKind::Variable => "Variable",
+            NameKind::Function => "Function",
+            NameKind::Type => "Type",
+        };
+        write!(f, "{} \"{}\" was not found at {}.", kind, self.name, self.span)
     }
 }
 
 impl Error for VariableNotFoundError {}
 
-/// かっこの数が一致しないときのエラーです。
+/// 対応するかっこがないときのエラーです。
 #[derive(Debug)]
-pub struct BracketError<'a> {
-    bracket_type: &'a str
+pub struct BracketError {
+    bracket_type: String,
+    span: Span,
 }
 
-impl <'a>BracketError<'a> {
-    pub fn new(bracket_type: &'a str) -> BracketError<'a> {
-        BracketError { bracket_type }
+impl BracketError {
+    pub fn new(bracket_type: String, span: Span) -> BracketError {
+        BracketError { bracket_type, span }
+    }
+
+    pub fn diagnostic(&self) -> Diagnostic {
+        match self.bracket_type.as_str() {
+            "(" | "{" | "[" => Diagnostic::new("this file contains an unclosed delimiter")
+                .with_primary(self.span, "unclosed delimiter"),
+            a => Diagnostic::new(format!("unexpected closing delimiter: `{}`", a))
+                .with_primary(self.span, "unexpected closing delimiter"),
+        }
     }
 }
 
-impl <'a>Display for BracketError<'a> {
+impl Display for BracketError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "There are no corresponding brackets to \"{}\"", self.bracket_type)
+        write!(f, "There are no corresponding brackets to \"{}\" at {}.", self.bracket_type, self.span)
     }
 }
 
-impl <'a>Error for BracketError<'a> {}
+impl Error for BracketError {}
 
 /// 無効な式が入力されたときのエラーです。
 #[derive(Debug)]
 pub struct InvalidExpressionError {
-    message: String
+    message: String,
+    span: Option<Span>,
 }
 
 impl InvalidExpressionError {
     pub fn new(str: String) -> InvalidExpressionError {
-        InvalidExpressionError { message: str.to_string() }
+        InvalidExpressionError { message: str.to_string(), span: None }
+    }
+
+    /// エラーが発生した位置を設定します。
+    /// - `span` - エラーが発生した位置
+    pub fn with_span(mut self, span: Span) -> InvalidExpressionError {
+        self.span = Some(span);
+        self
+    }
+
+    pub fn diagnostic(&self) -> Diagnostic {
+        let diagnostic = Diagnostic::new(self.message.clone());
+        match self.span {
+            Some(a) => diagnostic.with_primary(a, ""),
+            None => diagnostic,
+        }
     }
 }
 
@@ -60,7 +125,10 @@ impl From<&str> for InvalidExpressionError {
 
 impl Display for InvalidExpressionError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "Invalid expression detected.\n{}", self.message)
+        match self.span {
+            Some(a) => write!(f, "Invalid expression detected at {}.\n{}", a, self.message),
+            None => write!(f, "Invalid expression detected.\n{}", self.message),
+        }
     }
 }
 
@@ -68,34 +136,990 @@ impl Error for InvalidExpressionError {}
 
 /// voidと演算しようとしたときのエラーです。
 #[derive(Debug)]
-pub struct OperationError;
+pub struct OperationError {
+    span: Span,
+}
+
+impl OperationError {
+    pub fn new(span: Span) -> OperationError {
+        OperationError { span }
+    }
+
+    pub fn diagnostic(&self) -> Diagnostic {
+        Diagnostic::new("cannot operate with void")
+            .with_primary(self.span, "this operation uses a value of type `()`")
+    }
+}
 
 impl Display for OperationError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "Cannot operate with void.")
+        write!(f, "Cannot operate with void at {}.", self.span)
     }
 }
 
 impl Error for OperationError {}
 
+/// ムーブ済みの変数を使用しようとしたときのエラーです。
+#[derive(Debug)]
+pub struct MovedValueError {
+    name: String,
+    moved_at: Span,
+    used_at: Span,
+    in_previous_iteration: bool,
+    partially: bool,
+    clone: Option<String>,
+}
+
+impl MovedValueError {
+    pub fn new(name: String, moved_at: Span, used_at: Span) -> MovedValueError {
+        MovedValueError { name, moved_at, used_at, in_previous_iteration: false, partially: false, clone: None }
+    }
+
+    /// 値の一部のフィールドのみがムーブされていることを設定します。
+    pub fn partially(mut self) -> MovedValueError {
+        self.partially = true;
+        self
+    }
+
+    /// ムーブした位置で、ムーブする代わりに `.clone()` で複製することを提案します。
+    /// - `place` - ムーブされた場所
+    pub fn suggest_clone(mut self, place: String) -> MovedValueError {
+        self.clone = Some(place);
+        self
+    }
+
+    /// ループの前の繰り返しでムーブされた値であることを設定します。
+    pub fn in_previous_iteration(mut self) -> MovedValueError {
+        self.in_previous_iteration = true;
+        self
+    }
+
+    pub fn moved_at(&self) -> Span {
+        self.moved_at
+    }
+
+    pub fn used_at(&self) -> Span {
+        self.used_at
+    }
+
+    pub fn diagnostic(&self) -> Diagnostic {
+        let moved = if self.partially { "partially moved" } else { "moved" };
+        let label = if self.in_previous_iteration {
+            format!("value {} here, in previous iteration of loop", moved)
+        } else {
+            format!("value {} here", moved)
+        };
+        let mut diagnostic = Diagnostic::new(format!("use of {} value: `{}`", moved, self.name));
+        if let Some(place) = &self.clone {
+            diagnostic = diagnostic.with_help(format!("consider cloning the value if the performance cost is acceptable: `{}.clone()`", place));
+        }
+        if self.moved_at == self.used_at {
+            diagnostic.with_primary(self.used_at, label)
+        } else {
+            let used = if self.partially { "value used here after partial move" } else { "value used here after move" };
+            diagnostic
+                .with_primary(self.used_at, used)
+                .with_secondary(self.moved_at, label)
+        }
+    }
+}
+
+impl Display for MovedValueError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.partially {
+            write!(f, "Use of partially moved value \"{}\": value partially moved at {}, used at {}.", self.name, self.moved_at, self.used_at)
+        } else if self.in_previous_iteration {
+            write!(f, "Use of moved value \"{}\": value moved at {} in previous iteration of loop, used at {}.", self.name, self.moved_at, self.used_at)
+        } else {
+            write!(f, "Use of moved value \"{}\": value moved at {}, used at {}.", self.name, self.moved_at, self.used_at)
+        }
+    }
+}
+
+impl Error for MovedValueError {}
+
+/// 有効な借用と競合する借用を作成しようとしたときのエラーです。
+#[derive(Debug)]
+pub struct BorrowError {
+    name: String,
+    first_kind: BorrowKind,
+    first_at: Span,
+    second_kind: BorrowKind,
+    second_at: Span,
+}
+
+impl BorrowError {
+    pub fn new(name: String, first_kind: BorrowKind, first_at: Span, second_kind: BorrowKind, second_at: Span) -> BorrowError {
+        BorrowError { name, first_kind, first_at, second_kind, second_at }
+    }
+
+    pub fn diagnostic(&self) -> Diagnostic {
+        let (message, first, second) = match (self.first_kind, self.second_kind) {
+            (BorrowKind::Mutable, BorrowKind::Mutable) => (
+                format!("cannot borrow `{}` as mutable more than once at a time", self.name),
+                "first mutable borrow occurs here",
+                "second mutable borrow occurs here",
+            ),
+            (BorrowKind::Shared, _) => (
+                format!("cannot borrow `{}` as mutable because it is also borrowed as immutable", self.name),
+                "immutable borrow occurs here",
+                "mutable borrow occurs here",
+            ),
+            (BorrowKind::Mutable, BorrowKind::Shared) => (
+                format!("cannot borrow `{}` as immutable because it is also borrowed as mutable", self.name),
+                "mutable borrow occurs here",
+                "immutable borrow occurs here",
+            ),
+        };
+        Diagnostic::new(message)
+            .with_primary(self.second_at, second)
+            .with_secondary(self.first_at, first)
+    }
+}
+
+impl Display for BorrowError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (self.first_kind, self.second_kind) {
+            (BorrowKind::Mutable, BorrowKind::Mutable) => write!(
+                f,
+                "Cannot borrow \"{}\" as mutable more than once at a time: first mutable borrow at {}, second mutable borrow at {}.",
+                self.name, self.first_at, self.second_at
+            ),
+            (BorrowKind::Shared, _) => write!(
+                f,
+                "Cannot borrow \"{}\" as mutable because it is also borrowed as immutable: immutable borrow at {}, mutable borrow at {}.",
+                self.name, self.first_at, self.second_at
+            ),
+            (BorrowKind::Mutable, BorrowKind::Shared) => write!(
+                f,
+                "Cannot borrow \"{}\" as immutable because it is also borrowed as mutable: mutable borrow at {}, immutable borrow at {}.",
+                self.name, self.first_at, self.second_at
+            ),
+        }
+    }
+}
+
+impl Error for BorrowError {}
+
+/// 破棄される変数への参照がスコープの外に残るときのエラーです。
+#[derive(Debug)]
+pub struct DanglingReferenceError {
+    name: String,
+    borrowed_at: Span,
+    block: Span,
+    dropped_at: Span,
+}
+
+impl DanglingReferenceError {
+    pub fn new(name: String, borrowed_at: Span, block: Span, dropped_at: Span) -> DanglingReferenceError {
+        DanglingReferenceError { name, borrowed_at, block, dropped_at }
+    }
+
+    pub fn diagnostic(&self) -> Diagnostic {
+        Diagnostic::new(format!("`{}` does not live long enough", self.name))
+            .with_primary(self.borrowed_at, "borrowed value does not live long enough")
+            .with_secondary(self.dropped_at, format!("`{}` dropped here while still borrowed", self.name))
+    }
+}
+
+impl Display for DanglingReferenceError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Borrowed value \"{}\" does not live long enough: borrowed at {}, but dropped at {} at the end of the block starting at {} while still borrowed.",
+            self.name, self.borrowed_at, self.dropped_at, self.block
+        )
+    }
+}
+
+impl Error for DanglingReferenceError {}
+
+/// 期待される型と異なる型の値が使用されたときのエラーです。
+#[derive(Debug)]
+pub struct MismatchedTypesError {
+    expected: String,
+    found: String,
+    span: Span,
+    /// 推論された型の由来となった値のラベル
+    origins: Vec<Label>,
+}
+
+impl MismatchedTypesError {
+    pub fn new(expected: String, found: String, span: Span) -> MismatchedTypesError {
+        MismatchedTypesError { expected, found, span, origins: Vec::new() }
+    }
+
+    /// 推論された型の由来となった値の範囲を設定します。
+    /// - `expected_at` - 期待される型が推論された値の範囲
+    /// - `found_at` - 使用された値の型が推論された値の範囲
+    pub fn with_origins(mut self, expected_at: Option<Span>, found_at: Option<Span>) -> MismatchedTypesError {
+        if let Some(a) = expected_at.filter(|a| *a != self.span) {
+            self.origins.push(Label::new(a, "expected due to this value"));
+        }
+        if let Some(a) = found_at.filter(|a| *a != self.span && Some(*a) != expected_at) {
+            self.origins.push(Label::new(a, format!("type `{}` inferred from this value", self.found)));
+        }
+        self
+    }
+
+    pub fn diagnostic(&self) -> Diagnostic {
+        let diagnostic = Diagnostic::new("mismatched types")
+            .with_primary(self.span, format!("expected `{}`, found `{}`", self.expected, self.found));
+        self.origins.iter().fold(diagnostic, |a, b| a.with_secondary(b.span, b.message.clone()))
+    }
+}
+
+impl Display for MismatchedTypesError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Mismatched types at {}: expected \"{}\", found \"{}\".", self.span, self.expected, self.found)
+    }
+}
+
+impl Error for MismatchedTypesError {}
+
+/// 演算子を適用できない型の値に演算子を使用したときのエラーです。
+#[derive(Debug)]
+pub struct InvalidOperandError {
+    op: String,
+    ty: String,
+    span: Span,
+    is_unary: bool,
+}
+
+impl InvalidOperandError {
+    /// 二項演算子を適用できないときのエラーを作成します。
+    /// - `op` - 演算子
+    /// - `ty` - 左辺の型
+    /// - `span` - 二項式の範囲
+    pub fn binary(op: String, ty: String, span: Span) -> InvalidOperandError {
+        InvalidOperandError { op, ty, span, is_unary: false }
+    }
+
+    /// 単項演算子を適用できないときのエラーを作成します。
+    /// - `op` - 演算子
+    /// - `ty` - 被演算子の型
+    /// - `span` - 単項式の範囲
+    pub fn unary(op: String, ty: String, span: Span) -> InvalidOperandError {
+        InvalidOperandError { op, ty, span, is_unary: true }
+    }
+
+    pub fn diagnostic(&self) -> Diagnostic {
+        if self.is_unary {
+            Diagnostic::new(format!("cannot apply unary operator `{}` to type `{}`", self.op, self.ty))
+                .with_primary(self.span, format!("cannot apply unary operator `{}`", self.op))
+        } else {
+            Diagnostic::new(format!("binary operation `{}` cannot be applied to type `{}`", self.op, self.ty))
+                .with_primary(self.span, format!("`{}` cannot be used with `{}`", self.op, self.ty))
+        }
+    }
+}
+
+impl Display for InvalidOperandError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Operator \"{}\" cannot be applied to type \"{}\" at {}.", self.op, self.ty, self.span)
+    }
+}
+
+impl Error for InvalidOperandError {}
+
+/// 構造体のフィールドの誤りを表すエラーです。
+#[derive(Debug)]
+pub struct FieldError {
+    error_type: FieldErrorType,
+    ty: String,
+    span: Span,
+}
+
+impl FieldError {
+    /// 存在しないフィールドにアクセスしたときのエラーを作成します。
+    /// - `field` - フィールド名
+    /// - `ty` - アクセスした値の型
+    /// - `span` - フィールド名の範囲
+    pub fn no_field(field: String, ty: String, span: Span) -> Self {
+        FieldError { error_type: FieldErrorType::NoField(field), ty, span }
+    }
+
+    /// 構造体式に存在しないフィールドが書かれたときのエラーを作成します。
+    /// - `field` - フィールド名
+    /// - `ty` - 構造体名
+    /// - `span` - フィールド名の範囲
+    pub fn no_field_named(field: String, ty: String, span: Span) -> Self {
+        FieldError { error_type: FieldErrorType::NoFieldNamed(field), ty, span }
+    }
+
+    /// 構造体式にフィールドが不足しているときのエラーを作成します。
+    /// - `fields` - 不足しているフィールド名
+    /// - `ty` - 構造体名
+    /// - `span` - 構造体式の構造体名の範囲
+    pub fn missing(fields: Vec<String>, ty: String, span: Span) -> Self {
+        FieldError { error_type: FieldErrorType::Missing(fields), ty, span }
+    }
+
+    pub fn diagnostic(&self) -> Diagnostic {
+        match &self.error_type {
+            FieldErrorType::NoField(a) => Diagnostic::new(format!("no field `{}` on type `{}`", a, self.ty))
+                .with_primary(self.span, "unknown field"),
+            FieldErrorType::NoFieldNamed(a) => Diagnostic::new(format!("struct `{}` has no field named `{}`", self.ty, a))
+                .with_primary(self.span, format!("`{}` does not have this field", self.ty)),
+            FieldErrorType::Missing(fields) => {
+                let names: Vec<String> = fields.iter().map(|a| format!("`{}`", a)).collect();
+                let list = match names.split_last() {
+                    Some((last, init)) if !init.is_empty() => format!("{} and {}", init.join(", "), last),
+                    _ => names.concat(),
+                };
+                let noun = if fields.len() == 1 { "field" } else { "fields" };
+                Diagnostic::new(format!("missing {} {} in initializer of `{}`", noun, list, self.ty))
+                    .with_primary(self.span, format!("missing {}", list))
+            },
+        }
+    }
+}
+
+impl Display for FieldError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.error_type {
+            FieldErrorType::NoField(a) => write!(f, "No field \"{}\" on type \"{}\" at {}.", a, self.ty, self.span),
+            FieldErrorType::NoFieldNamed(a) => write!(f, "Struct \"{}\" has no field named \"{}\" at {}.", self.ty, a, self.span),
+            FieldErrorType::Missing(a) => write!(f, "Missing field(s) {:?} in initializer of \"{}\" at {}.", a, self.ty, self.span),
+        }
+    }
+}
+
+impl Error for FieldError {}
+
+#[derive(Debug)]
+pub enum FieldErrorType {
+    NoField(String),
+    NoFieldNamed(String),
+    Missing(Vec<String>),
+}
+
+/// 添字によるアクセスの誤りを表すエラーです。
+#[derive(Debug)]
+pub struct IndexError {
+    error_type: IndexErrorType,
+    span: Span,
+}
+
+impl IndexError {
+    /// 添字によるアクセスができない型の値にアクセスしたときのエラーを作成します。
+    /// - `ty` - アクセスした値の型
+    /// - `span` - 添字によるアクセスの範囲
+    pub fn not_indexable(ty: String, span: Span) -> Self {
+        IndexError { error_type: IndexErrorType::NotIndexable(ty), span }
+    }
+
+    /// 実行時に範囲外の添字でアクセスしたときのエラーを作成します。
+    /// - `len` - 要素の数
+    /// - `index` - 添字
+    /// - `span` - 添字によるアクセスの範囲
+    pub fn out_of_bounds(len: usize, index: i32, span: Span) -> Self {
+        IndexError { error_type: IndexErrorType::OutOfBounds { len, index }, span }
+    }
+
+    /// 実行時に空の可変長の配列から要素を取り出したときのエラーを作成します。
+    /// - `span` - メソッド呼び出しの範囲
+    pub fn empty(span: Span) -> Self {
+        IndexError { error_type: IndexErrorType::Empty, span }
+    }
+
+    pub fn diagnostic(&self) -> Diagnostic {
+        match &self.error_type {
+            IndexErrorType::NotIndexable(a) => Diagnostic::new(format!("cannot index into a value of type `{}`", a))
+                .with_primary(self.span, ""),
+            IndexErrorType::OutOfBounds { len, index } => Diagnostic::new(format!("index out of bounds: the len is {} but the index is {}", len, index))
+                .with_primary(self.span, "index out of bounds"),
+            IndexErrorType::Empty => Diagnostic::new("cannot pop from an empty `Vec`")
+                .with_primary(self.span, "the vector is empty"),
+        }
+    }
+}
+
+impl Display for IndexError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.error_type {
+            IndexErrorType::NotIndexable(a) => write!(f, "Cannot index into a value of type \"{}\" at {}.", a, self.span),
+            IndexErrorType::OutOfBounds { len, index } => write!(f, "Index out of bounds: the len is {} but the index is {} at {}.", len, index, self.span),
+            IndexErrorType::Empty => write!(f, "Cannot pop from an empty vector at {}.", self.span),
+        }
+    }
+}
+
+impl Error for IndexError {}
+
+#[derive(Debug)]
+pub enum IndexErrorType {
+    NotIndexable(String),
+    OutOfBounds { len: usize, index: i32 },
+    Empty,
+}
+
+/// 配列の要素のように、一部だけをムーブできない値からムーブしたときのエラーです。
+#[derive(Debug)]
+pub struct MoveOutError {
+    is_array: bool,
+    place: String,
+    ty: String,
+    span: Span,
+    help: Vec<String>,
+}
+
+impl MoveOutError {
+    /// 配列の要素をムーブしたときのエラーを作成します。
+    /// - `place` - ムーブした場所 (`a[_]` の形式)
+    /// - `ty` - 配列の型
+    /// - `element` - 要素の型
+    /// - `span` - ムーブした範囲
+    pub fn array(place: String, ty: String, element: String, span: Span) -> MoveOutError {
+        let help = Self::help(&place, &element);
+        MoveOutError { is_array: true, place, ty, span, help }
+    }
+
+    /// 可変長の配列の要素を添字によってムーブしたときのエラーを作成します。
+    /// - `place` - ムーブした場所 (`v[_]` の形式)
+    /// - `ty` - 可変長の配列の型
+    /// - `element` - 要素の型
+    /// - `span` - ムーブした範囲
+    pub fn index(place: String, ty: String, element: String, span: Span) -> MoveOutError {
+        let help = Self::help(&place, &element);
+        MoveOutError { is_array: false, place, ty, span, help }
+    }
+
+    /// ムーブした式を借用することを、式の表記と共に提案します。
+    /// - `expression` - ムーブした式の表記 (`a[0]` など)
+    pub fn suggest_borrow(mut self, expression: String) -> MoveOutError {
+        self.help[1] = format!("consider borrowing here: `&{}`", expression);
+        self
+    }
+
+    /// ムーブできない理由と、借用することの提案を返します。
+    /// - `place` - ムーブした場所
+    /// - `element` - 要素の型
+    fn help(place: &str, element: &str) -> Vec<String> {
+        vec![
+            format!("move occurs because `{}` has type `{}`, which does not implement the `Copy` trait", place, element),
+            "consider borrowing the element with `&` instead of moving it".to_string(),
+        ]
+    }
+
+    pub fn diagnostic(&self) -> Diagnostic {
+        let message = if self.is_array {
+            format!("cannot move out of type `{}`, a non-copy array", self.ty)
+        } else {
+            format!("cannot move out of index of `{}`", self.ty)
+        };
+        self.help.iter().fold(
+            Diagnostic::new(message).with_primary(self.span, "cannot move out of here"),
+            |diagnostic, a| diagnostic.with_help(a.clone()),
+        )
+    }
+}
+
+impl Display for MoveOutError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Cannot move \"{}\" out of type \"{}\" at {}.", self.place, self.ty, self.span)
+    }
+}
+
+impl Error for MoveOutError {}
+
+/// 値の型に存在しないメソッドを呼び出したときのエラーです。
+#[derive(Debug)]
+pub struct MethodError {
+    name: String,
+    ty: String,
+    span: Span,
+}
+
+impl MethodError {
+    pub fn new(name: String, ty: String, span: Span) -> MethodError {
+        MethodError { name, ty, span }
+    }
+
+    pub fn diagnostic(&self) -> Diagnostic {
+        Diagnostic::new(format!("no method named `{}` found for type `{}` in the current scope", self.name, self.ty))
+            .with_primary(self.span, format!("method not found in `{}`", self.ty))
+    }
+}
+
+impl Display for MethodError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "No method named \"{}\" found for type \"{}\" at {}.", self.name, self.ty, self.span)
+    }
+}
+
+impl Error for MethodError {}
+
+/// 借用されている値をムーブしようとしたときのエラーです。
+#[derive(Debug)]
+pub struct BorrowedMoveError {
+    name: String,
+    borrowed: String,
+    borrowed_at: Span,
+    span: Span,
+}
+
+impl BorrowedMoveError {
+    pub fn new(name: String, borrowed: String, borrowed_at: Span, span: Span) -> BorrowedMoveError {
+        BorrowedMoveError { name, borrowed, borrowed_at, span }
+    }
+
+    pub fn diagnostic(&self) -> Diagnostic {
+        Diagnostic::new(format!("cannot move out of `{}` because it is borrowed", self.name))
+            .with_primary(self.span, format!("move out of `{}` occurs here", self.name))
+            .with_secondary(self.borrowed_at, format!("borrow of `{}` occurs here", self.borrowed))
+    }
+}
+
+impl Display for BorrowedMoveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Cannot move out of \"{}\" because it is borrowed: borrowed at {}, moved at {}.", self.name, self.borrowed_at, self.span)
+    }
+}
+
+impl Error for BorrowedMoveError {}
+
+/// 借用されている値を読み出したり、代入したりしようとしたときのエラーです。
+#[derive(Debug)]
+pub struct BorrowedAccessError {
+    error_type: BorrowedAccessErrorType,
+    name: String,
+    borrowed: String,
+    borrowed_at: Span,
+    span: Span,
+}
+
+impl BorrowedAccessError {
+    /// 可変として借用されている値を読み出したときのエラーを作成します。
+    /// - `name` - 読み出した場所
+    /// - `borrowed` - 借用されている場所
+    /// - `borrowed_at` - 借用した範囲
+    /// - `span` - 読み出した範囲
+    pub fn used(name: String, borrowed: String, borrowed_at: Span, span: Span) -> Self {
+        BorrowedAccessError { error_type: BorrowedAccessErrorType::Use, name, borrowed, borrowed_at, span }
+    }
+
+    /// 借用されている場所へ代入したときのエラーを作成します。
+    /// - `name` - 代入した場所
+    /// - `borrowed` - 借用されている場所
+    /// - `borrowed_at` - 借用した範囲
+    /// - `span` - 代入した範囲
+    pub fn assigned(name: String, borrowed: String, borrowed_at: Span, span: Span) -> Self {
+        BorrowedAccessError { error_type: BorrowedAccessErrorType::Assign, name, borrowed, borrowed_at, span }
+    }
+
+    pub fn diagnostic(&self) -> Diagnostic {
+        let (message, label) = match self.error_type {
+            BorrowedAccessErrorType::Use => (
+                format!("cannot use `{}` because it was mutably borrowed", self.name),
+                format!("use of borrowed `{}`", self.borrowed),
+            ),
+            BorrowedAccessErrorType::Assign => (
+                format!("cannot assign to `{}` because it is borrowed", self.name),
+                format!("`{}` is assigned to here but it was already borrowed", self.name),
+            ),
+        };
+        Diagnostic::new(message)
+            .with_primary(self.span, label)
+            .with_secondary(self.borrowed_at, format!("`{}` is borrowed here", self.borrowed))
+    }
+}
+
+impl Display for BorrowedAccessError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.error_type {
+            BorrowedAccessErrorType::Use => write!(
+                f,
+                "Cannot use \"{}\" because it was mutably borrowed: borrowed at {}, used at {}.",
+                self.name, self.borrowed_at, self.span
+            ),
+            BorrowedAccessErrorType::Assign => write!(
+                f,
+                "Cannot assign to \"{}\" because it is borrowed: borrowed at {}, assigned at {}.",
+                self.name, self.borrowed_at, self.span
+            ),
+        }
+    }
+}
+
+impl Error for BorrowedAccessError {}
+
+#[derive(Debug)]
+pub enum BorrowedAccessErrorType {
+    Use,
+    Assign,
+}
+
+/// 構造体が、参照や `Box`、`Vec` を介さずに自身を含むときのエラーです。
+#[derive(Debug)]
+pub struct RecursiveTypeError {
+    names: Vec<String>,
+    span: Span,
+    fields: Vec<Span>,
+}
+
+impl RecursiveTypeError {
+    /// 再帰的な構造体のエラーを作成します。
+    /// - `names` - 循環に含まれる構造体名
+    /// - `span` - 最初の構造体名の範囲
+    /// - `fields` - 循環をたどるフィールドの範囲
+    pub fn new(names: Vec<String>, span: Span, fields: Vec<Span>) -> RecursiveTypeError {
+        RecursiveTypeError { names, span, fields }
+    }
+
+    pub fn diagnostic(&self) -> Diagnostic {
+        let names: Vec<String> = self.names.iter().map(|a| format!("`{}`", a)).collect();
+        let message = match names.split_last() {
+            Some((last, init)) if !init.is_empty() => format!("recursive types {} and {} have infinite size", init.join(", "), last),
+            _ => format!("recursive type {} has infinite size", names.join("")),
+        };
+        self.fields
+            .iter()
+            .fold(Diagnostic::new(message).with_primary(self.span, ""), |diagnostic, a| diagnostic.with_secondary(*a, "recursive without indirection"))
+            .with_help("insert some indirection (e.g., a `Box` or `Vec`) to break the cycle")
+    }
+}
+
+impl Display for RecursiveTypeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Recursive type \"{}\" has infinite size at {}.", self.names.join("\", \""), self.span)
+    }
+}
+
+impl Error for RecursiveTypeError {}
+
+/// `Copy` でないフィールドを持つ構造体に `#[derive(Copy)]` を指定したときのエラーです。
+#[derive(Debug)]
+pub struct CopyError {
+    name: String,
+    span: Span,
+    fields: Vec<Span>,
+}
+
+impl CopyError {
+    pub fn new(name: String, span: Span, fields: Vec<Span>) -> CopyError {
+        CopyError { name, span, fields }
+    }
+
+    pub fn diagnostic(&self) -> Diagnostic {
+        self.fields.iter().fold(
+            Diagnostic::new("the trait `Copy` cannot be implemented for this type").with_primary(self.span, ""),
+            |diagnostic, a| diagnostic.with_secondary(*a, "this field does not implement `Copy`"),
+        )
+    }
+}
+
+impl Display for CopyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "The trait \"Copy\" cannot be implemented for struct \"{}\" at {}.", self.name, self.span)
+    }
+}
+
+impl Error for CopyError {}
+
+/// 関数呼び出しが深くなりすぎたときのエラーです。
+#[derive(Debug)]
+pub struct RecursionLimitError {
+    name: String,
+    span: Span,
+}
+
+impl RecursionLimitError {
+    pub fn new(name: String, span: Span) -> RecursionLimitError {
+        RecursionLimitError { name, span }
+    }
+
+    pub fn diagnostic(&self) -> Diagnostic {
+        Diagnostic::new(format!("recursion limit reached while calling `{}`", self.name))
+            .with_primary(self.span, "called here")
+    }
+}
+
+impl Display for RecursionLimitError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Recursion limit reached while calling \"{}\" at {}.", self.name, self.span)
+    }
+}
+
+impl Error for RecursionLimitError {}
+
+/// 関数の戻り値が、関数内の変数への借用を保持しているときのエラーです。
+#[derive(Debug)]
+pub struct ReturnLocalReferenceError {
+    name: String,
+    is_parameter: bool,
+    borrowed_at: Span,
+    returned_at: Span,
+}
+
+impl ReturnLocalReferenceError {
+    pub fn new(name: String, is_parameter: bool, borrowed_at: Span, returned_at: Span) -> ReturnLocalReferenceError {
+        ReturnLocalReferenceError { name, is_parameter, borrowed_at, returned_at }
+    }
+
+    pub fn diagnostic(&self) -> Diagnostic {
+        let kind = if self.is_parameter { "function parameter" } else { "local variable" };
+        if self.borrowed_at == self.returned_at {
+            Diagnostic::new(format!("cannot return reference to {} `{}`", kind, self.name))
+                .with_primary(self.returned_at, "returns a reference to data owned by the current function")
+        } else {
+            Diagnostic::new(format!("cannot return value referencing {} `{}`", kind, self.name))
+                .with_primary(self.returned_at, "returns a value referencing data owned by the current function")
+                .with_secondary(self.borrowed_at, format!("`{}` is borrowed here", self.name))
+        }
+    }
+}
+
+impl Display for ReturnLocalReferenceError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Cannot return a reference to \"{}\" owned by the current function: borrowed at {}, returned at {}.",
+            self.name, self.borrowed_at, self.returned_at
+        )
+    }
+}
+
+impl Error for ReturnLocalReferenceError {}
+
+/// 関数の定義で寿命の指定に誤りがあるときのエラーです。
+#[derive(Debug)]
+pub struct LifetimeError {
+    error_type: LifetimeErrorType,
+    span: Span,
+}
+
+impl LifetimeError {
+    /// 戻り値の参照の寿命を省略できないときのエラーを作成します。
+    /// - `span` - 寿命が省略された参照の範囲
+    /// - `candidates` - 参照を受け取る引数名
+    /// - `inputs` - 参照を含む引数の型の範囲
+    pub fn missing(span: Span, candidates: Vec<String>, inputs: Vec<Span>) -> Self {
+        LifetimeError { error_type: LifetimeErrorType::Missing { candidates, inputs }, span }
+    }
+
+    /// 構造体のフィールドの型に、`'static` 以外の寿命を持つ参照が含まれるときのエラーを作成します。
+    /// - `span` - 参照の範囲
+    pub fn in_struct(span: Span) -> Self {
+        LifetimeError { error_type: LifetimeErrorType::InStruct, span }
+    }
+
+    /// 宣言されていない寿命が使用されたときのエラーを作成します。
+    /// - `name` - 寿命の名前
+    /// - `span` - 寿命の名前の範囲
+    pub fn undeclared(name: String, span: Span) -> Self {
+        LifetimeError { error_type: LifetimeErrorType::Undeclared(name), span }
+    }
+
+    /// 関数が、戻り値の寿命とは異なる寿命を持つ引数の参照を返したときのエラーを作成します。
+    /// - `expected` - 戻り値の寿命の名前
+    /// - `returned` - 返した参照の寿命の名前 (省略されていれば、参照を受け取った引数名)
+    /// - `elided` - 返した参照の寿命が省略されているか
+    /// - `defined_at` - 返した参照の寿命が書かれた範囲 (省略されていれば `&` の範囲)
+    /// - `span` - 戻り値となる式の範囲
+    pub fn mismatch(expected: String, returned: String, elided: bool, defined_at: Span, span: Span) -> Self {
+        LifetimeError { error_type: LifetimeErrorType::Mismatch { expected, returned, elided, defined_at }, span }
+    }
+
+    pub fn diagnostic(&self) -> Diagnostic {
+        match &self.error_type {
+            LifetimeErrorType::Missing { candidates, inputs } => {
+                let help = match candidates.split_last() {
+                    None => "this function's return type contains a borrowed value, but there is no value for it to be borrowed from".to_string(),
+                    Some((a, [])) => format!(
+                        "this function's return type contains a borrowed value, but the signature does not say which one of `{}`'s lifetimes it is borrowed from",
+                        a
+                    ),
+                    Some((last, init)) => format!(
+                        "this function's return type contains a borrowed value, but the signature does not say whether it is borrowed from {} or `{}`",
+                        init.iter().map(|a| format!("`{}`", a)).collect::<Vec<_>>().join(", "),
+                        last
+                    ),
+                };
+                let diagnostic = inputs.iter().fold(
+                    Diagnostic::new("missing lifetime specifier").with_primary(self.span, "expected named lifetime parameter"),
+                    |a, b| a.with_secondary(*b, ""),
+                );
+                diagnostic.with_help(help)
+            },
+            LifetimeErrorType::Undeclared(name) => Diagnostic::new(format!("use of undeclared lifetime name `{}`", name))
+                .with_primary(self.span, "undeclared lifetime"),
+            LifetimeErrorType::InStruct => Diagnostic::new("missing lifetime specifier")
+                .with_primary(self.span, "expected named lifetime parameter")
+                .with_help("structs cannot declare lifetime parameters; store an owned value or a `&'static` reference instead"),
+            LifetimeErrorType::Mismatch { expected, returned, elided: false, defined_at } => Diagnostic::new("lifetime may not live long enough")
+                .with_secondary(*defined_at, format!("lifetime `{}` defined here", returned))
+                .with_primary(
+                    self.span,
+                    format!("function was supposed to return data with lifetime `{}` but it is returning data with lifetime `{}`", expected, returned),
+                ),
+            LifetimeErrorType::Mismatch { expected, returned: parameter, elided: true, defined_at } => Diagnostic::new("lifetime may not live long enough")
+                .with_secondary(*defined_at, format!("the lifetime of this reference in `{}` is elided", parameter))
+                .with_primary(self.span, format!("function was supposed to return data with lifetime `{}` but it is returning data from `{}`", expected, parameter))
+                .with_help(format!("add explicit lifetime `{}` to the type of `{}`", expected, parameter)),
+        }
+    }
+}
+
+impl Display for LifetimeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.error_type {
+            LifetimeErrorType::Missing { .. } => write!(f, "Missing lifetime specifier at {}.", self.span),
+            LifetimeErrorType::Undeclared(a) => write!(f, "Use of undeclared lifetime name \"{}\" at {}.", a, self.span),
+            LifetimeErrorType::InStruct => write!(f, "Missing lifetime specifier in struct field at {}.", self.span),
+            LifetimeErrorType::Mismatch { expected, returned, .. } => {
+                write!(f, "Lifetime of \"{}\" may not live as long as \"{}\" at {}.", returned, expected, self.span)
+            },
+        }
+    }
+}
+
+impl Error for LifetimeError {}
+
+#[derive(Debug)]
+pub enum LifetimeErrorType {
+    Missing { candidates: Vec<String>, inputs: Vec<Span> },
+    Undeclared(String),
+    InStruct,
+    Mismatch { expected: String, returned: String, elided: bool, defined_at: Span },
+}
+
+/// 不変な変数を変更しようとしたときのエラーです。
+#[derive(Debug)]
+pub struct MutabilityError {
+    error_type: MutabilityErrorType,
+    name: String,
+    place: String,
+    span: Span,
+}
+
+impl MutabilityError {
+    /// 初期化済みの不変な変数に再び代入したときのエラーを作成します。
+    /// - `name` - 変数名
+    /// - `first_at` - 最初に代入された範囲
+    /// - `is_parameter` - 変数が関数の引数であるか
+    /// - `span` - 再び代入した範囲
+    pub fn assign_twice(name: String, first_at: Span, is_parameter: bool, span: Span) -> Self {
+        let place = name.clone();
+        MutabilityError { error_type: MutabilityErrorType::AssignTwice { first_at, is_parameter }, name, place, span }
+    }
+
+    /// 不変な変数やそのフィールドを可変として借用したときのエラーを作成します。
+    /// - `name` - 変数名
+    /// - `place` - 借用した場所 (`a` や `a.b`)
+    /// - `span` - 借用した範囲
+    pub fn borrow_mutable(name: String, place: String, span: Span) -> Self {
+        MutabilityError { error_type: MutabilityErrorType::BorrowMutable, name, place, span }
+    }
+
+    /// 不変な変数のフィールドに代入したときのエラーを作成します。
+    /// - `name` - 変数名
+    /// - `place` - 代入した場所 (`a.b`)
+    /// - `span` - 代入した範囲
+    pub fn assign_field(name: String, place: String, span: Span) -> Self {
+        MutabilityError { error_type: MutabilityErrorType::AssignField, name, place, span }
+    }
+
+    /// 変数名に続く、変数が不変であることの説明を返します。
+    fn reason(&self) -> String {
+        if self.place == self.name {
+            "as it is not declared as mutable".to_string()
+        } else {
+            format!("as `{}` is not declared as mutable", self.name)
+        }
+    }
+
+    pub fn diagnostic(&self) -> Diagnostic {
+        match self.error_type {
+            MutabilityErrorType::AssignTwice { is_parameter: true, .. } => {
+                Diagnostic::new(format!("cannot assign to immutable argument `{}`", self.name))
+                    .with_primary(self.span, "cannot assign to immutable argument")
+                    .with_help(format!("consider making this binding mutable: `mut {}`", self.name))
+            },
+            MutabilityErrorType::AssignTwice { first_at, .. } => {
+                Diagnostic::new(format!("cannot assign twice to immutable variable `{}`", self.name))
+                    .with_secondary(first_at, format!("first assignment to `{}`", self.name))
+                    .with_primary(self.span, "cannot assign twice to immutable variable")
+                    .with_help(format!("consider making this binding mutable: `mut {}`", self.name))
+            },
+            MutabilityErrorType::BorrowMutable => {
+                Diagnostic::new(format!("cannot borrow `{}` as mutable, {}", self.place, self.reason()))
+                    .with_primary(self.span, "cannot borrow as mutable")
+                    .with_help(format!("consider changing this to be mutable: `mut {}`", self.name))
+            },
+            MutabilityErrorType::AssignField => {
+                Diagnostic::new(format!("cannot assign to `{}`, {}", self.place, self.reason()))
+                    .with_primary(self.span, "cannot assign")
+                    .with_help(format!("consider changing this to be mutable: `mut {}`", self.name))
+            },
+        }
+    }
+}
+
+impl Display for MutabilityError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.error_type {
+            MutabilityErrorType::AssignTwice { .. } => write!(f, "Cannot assign twice to immutable variable \"{}\" at {}.", self.name, self.span),
+            MutabilityErrorType::BorrowMutable => write!(f, "Cannot borrow \"{}\" of immutable variable as mutable at {}.", self.place, self.span),
+            MutabilityErrorType::AssignField => write!(f, "Cannot assign to \"{}\" of immutable variable at {}.", self.place, self.span),
+        }
+    }
+}
+
+impl Error for MutabilityError {}
+
+#[derive(Debug)]
+pub enum MutabilityErrorType {
+    AssignTwice { first_at: Span, is_parameter: bool },
+    BorrowMutable,
+    AssignField,
+}
+
 #[derive(Debug)]
 pub struct ReferenceError {
-    error_type: ReferenceErrorType
+    error_type: ReferenceErrorType,
+    span: Span,
 }
 
 impl ReferenceError {
-    pub fn invalid_dereference() -> Self {
-        ReferenceError { error_type: ReferenceErrorType::InvalidDereference }
+    pub fn invalid_dereference(span: Span) -> Self {
+        ReferenceError { error_type: ReferenceErrorType::InvalidDereference, span }
     }
 
-    pub fn uninitialized() -> Self {
-        ReferenceError { error_type: ReferenceErrorType::Uninitialized }
+    /// 初期化されていない可能性がある変数を使用したときのエラーを作成します。
+    /// - `name` - 変数名
+    /// - `declared_at` - 変数が宣言された範囲
+    /// - `span` - 変数を使用した範囲
+    pub fn uninitialized(name: String, declared_at: Span, span: Span) -> Self {
+        ReferenceError { error_type: ReferenceErrorType::Uninitialized { name, declared_at }, span }
+    }
+
+    pub fn behind_shared_reference(span: Span) -> Self {
+        ReferenceError { error_type: ReferenceErrorType::BehindSharedReference, span }
+    }
+
+    pub fn borrow_behind_shared_reference(span: Span) -> Self {
+        ReferenceError { error_type: ReferenceErrorType::BorrowBehindSharedReference, span }
+    }
+
+    pub fn diagnostic(&self) -> Diagnostic {
+        match &self.error_type {
+            ReferenceErrorType::InvalidDereference => Diagnostic::new("value cannot be dereferenced")
+                .with_primary(self.span, "not a reference"),
+            ReferenceErrorType::Uninitialized { name, declared_at } => Diagnostic::new(format!("used binding `{}` isn't initialized", name))
+                .with_secondary(*declared_at, "binding declared here but left uninitialized")
+                .with_primary(self.span, format!("`{}` used here but it isn't initialized", name)),
+            ReferenceErrorType::BehindSharedReference => Diagnostic::new("cannot assign to data in a `&` reference")
+                .with_primary(self.span, "cannot assign"),
+            ReferenceErrorType::BorrowBehindSharedReference => Diagnostic::new("cannot borrow data in a `&` reference as mutable")
+                .with_primary(self.span, "cannot borrow as mutable"),
+        }
     }
 }
 
 impl Display for ReferenceError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.error_type)
+        write!(f, "{} ({})", self.error_type, self.span)
     }
 }
 
@@ -104,25 +1128,292 @@ impl Error for ReferenceError {}
 #[derive(Debug)]
 pub enum ReferenceErrorType {
     InvalidDereference,
-    Uninitialized,
+    Uninitialized { name: String, declared_at: Span },
+    BehindSharedReference,
+    BorrowBehindSharedReference,
 }
 
 impl Display for ReferenceErrorType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", match self {
-            ReferenceErrorType::InvalidDereference => "Invalid dereference.",
-            ReferenceErrorType::Uninitialized => "Variable was uninitialized.",
-        })
+        match self {
+            ReferenceErrorType::InvalidDereference => write!(f, "Invalid dereference."),
+            ReferenceErrorType::Uninitialized { name, .. } => write!(f, "Variable \"{}\" was uninitialized.", name),
+            ReferenceErrorType::BehindSharedReference => write!(f, "Cannot mutate a value behind a shared reference."),
+            ReferenceErrorType::BorrowBehindSharedReference => write!(f, "Cannot borrow a value behind a shared reference as mutable."),
+        }
+    }
+}
+
+/// 参照をたどった先の値をムーブしようとしたときのエラーです。
+#[derive(Debug)]
+pub struct MoveBehindReferenceError {
+    place: Option<String>,
+    kind: BorrowKind,
+    ty: String,
+    span: Span,
+}
+
+impl MoveBehindReferenceError {
+    /// - `place` - ムーブした場所 (`*r` や `r.name` の形式、変数から始まる場所でなければ `None`)
+    /// - `kind` - たどった参照の種類
+    /// - `ty` - ムーブした値の型
+    /// - `span` - ムーブした範囲
+    pub fn new(place: Option<String>, kind: BorrowKind, ty: String, span: Span) -> MoveBehindReferenceError {
+        MoveBehindReferenceError { place, kind, ty, span }
+    }
+
+    pub fn diagnostic(&self) -> Diagnostic {
+        let reference = match self.kind {
+            BorrowKind::Shared => "shared",
+            BorrowKind::Mutable => "mutable",
+        };
+        match &self.place {
+            Some(a) => Diagnostic::new(format!("cannot move out of `{}` which is behind a {} reference", a, reference))
+                .with_primary(self.span, format!("move occurs because `{}` has type `{}`, which does not implement the `Copy` trait", a, self.ty))
+                .with_help(format!("consider borrowing here: `&{}`", a)),
+            None => Diagnostic::new(format!("cannot move out of a {} reference", reference))
+                .with_primary(self.span, format!("move occurs because value has type `{}`, which does not implement the `Copy` trait", self.ty)),
+        }
+    }
+}
+
+impl Display for MoveBehindReferenceError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.place {
+            Some(a) => write!(f, "Cannot move out of \"{}\" which is behind a reference at {}.", a, self.span),
+            None => write!(f, "Cannot move out of a reference at {}.", self.span),
+        }
     }
 }
 
-/// `Error` を実装した構造体をボックス化して `Result` 列挙型に入れたものを返すマクロです。
+impl Error for MoveBehindReferenceError {}
+
+/// パーサが返すすべてのエラーをまとめた列挙型です。
+/// 各エラーには `--explain` で説明を表示できる固定のエラーコードが割り当てられています。
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum ParseError {
+    /// 未定義の変数 (E0001)
+    VariableNotFound(VariableNotFoundError),
+    /// 対応しないかっこ (E0002)
+    Bracket(BracketError),
+    /// 無効な式 (E0003)
+    InvalidExpression(InvalidExpressionError),
+    /// voidとの演算 (E0004)
+    Operation(OperationError),
+    /// ムーブ済みの値の使用 (E0005)
+    MovedValue(MovedValueError),
+    /// 競合する借用 (E0006, E0007)
+    Borrow(BorrowError),
+    /// 破棄される値への参照 (E0008)
+    DanglingReference(DanglingReferenceError),
+    /// 参照の不正な操作 (E0009, E0010, E0011)
+    Reference(ReferenceError),
+    /// 型の不一致 (E0012)
+    MismatchedTypes(MismatchedTypesError),
+    /// 関数呼び出しの深さの上限 (E0013)
+    RecursionLimit(RecursionLimitError),
+    /// 寿命の指定の誤り (E0014, E0015, E0032)
+    Lifetime(LifetimeError),
+    /// 関数内の変数への参照を返す (E0016)
+    ReturnLocalReference(ReturnLocalReferenceError),
+    /// 演算子を適用できない型 (E0017)
+    InvalidOperand(InvalidOperandError),
+    /// 不変な変数の変更 (E0018, E0019, E0022)
+    Mutability(MutabilityError),
+    /// 構造体のフィールドの誤り (E0020, E0021)
+    Field(FieldError),
+    /// 添字によるアクセスの誤りと、空の可変長の配列からの取り出し (E0023, E0024, E0034)
+    Index(IndexError),
+    /// 配列の要素などのムーブ (E0025)
+    MoveOut(MoveOutError),
+    /// 存在しないメソッドの呼び出し (E0026)
+    Method(MethodError),
+    /// 借用されている値のムーブ (E0027)
+    BorrowedMove(BorrowedMoveError),
+    /// `Copy` にできない構造体への `#[derive(Copy)]` (E0028)
+    Copy(CopyError),
+    /// 参照をたどった先の値のムーブ (E0029)
+    MoveBehindReference(MoveBehindReferenceError),
+    /// 借用されている値の読み出しと代入 (E0030, E0031)
+    BorrowedAccess(BorrowedAccessError),
+    /// 無限の大きさを持つ再帰的な構造体 (E0033)
+    RecursiveType(RecursiveTypeError),
+}
+
+impl ParseError {
+    /// エラーコードを返します。
+    pub fn code(&self) -> &'static str {
+        match self {
+            ParseError::VariableNotFound(_) => "E0001",
+            ParseError::Bracket(_) => "E0002",
+            ParseError::InvalidExpression(_) => "E0003",
+            ParseError::Operation(_) => "E0004",
+            ParseError::MovedValue(_) => "E0005",
+            ParseError::Borrow(a) => match (a.first_kind, a.second_kind) {
+                (BorrowKind::Mutable, BorrowKind::Mutable) => "E0006",
+                _ => "E0007",
+            },
+            ParseError::DanglingReference(_) => "E0008",
+            ParseError::Reference(a) => match &a.error_type {
+                ReferenceErrorType::InvalidDereference => "E0009",
+                ReferenceErrorType::Uninitialized { .. } => "E0010",
+                ReferenceErrorType::BehindSharedReference | ReferenceErrorType::BorrowBehindSharedReference => "E0011",
+            },
+            ParseError::MismatchedTypes(_) => "E0012",
+            ParseError::RecursionLimit(_) => "E0013",
+            ParseError::Lifetime(a) => match a.error_type {
+                LifetimeErrorType::Missing { .. } | LifetimeErrorType::InStruct => "E0014",
+                LifetimeErrorType::Undeclared(_) => "E0015",
+                LifetimeErrorType::Mismatch { .. } => "E0032",
+            },
+            ParseError::ReturnLocalReference(_) => "E0016",
+            ParseError::InvalidOperand(_) => "E0017",
+            ParseError::Mutability(a) => match a.error_type {
+                MutabilityErrorType::AssignTwice { .. } => "E0018",
+                MutabilityErrorType::BorrowMutable => "E0019",
+                MutabilityErrorType::AssignField => "E0022",
+            },
+            ParseError::Field(a) => match a.error_type {
+                FieldErrorType::NoField(_) | FieldErrorType::NoFieldNamed(_) => "E0020",
+                FieldErrorType::Missing(_) => "E0021",
+            },
+            ParseError::Index(a) => match a.error_type {
+                IndexErrorType::NotIndexable(_) => "E0023",
+                IndexErrorType::OutOfBounds { .. } => "E0024",
+                IndexErrorType::Empty => "E0034",
+            },
+            ParseError::MoveOut(_) => "E0025",
+            ParseError::Method(_) => "E0026",
+            ParseError::BorrowedMove(_) => "E0027",
+            ParseError::Copy(_) => "E0028",
+            ParseError::MoveBehindReference(_) => "E0029",
+            ParseError::BorrowedAccess(a) => match a.error_type {
+                BorrowedAccessErrorType::Use => "E0030",
+                BorrowedAccessErrorType::Assign => "E0031",
+            },
+            ParseError::RecursiveType(_) => "E0033",
+        }
+    }
+
+    /// エラーコード付きの診断情報を作成します。
+    pub fn diagnostic(&self) -> Diagnostic {
+        let diagnostic = match self {
+            ParseError::VariableNotFound(a) => a.diagnostic(),
+            ParseError::Bracket(a) => a.diagnostic(),
+            ParseError::InvalidExpression(a) => a.diagnostic(),
+            ParseError::Operation(a) => a.diagnostic(),
+            ParseError::MovedValue(a) => a.diagnostic(),
+            ParseError::Borrow(a) => a.diagnostic(),
+            ParseError::DanglingReference(a) => a.diagnostic(),
+            ParseError::Reference(a) => a.diagnostic(),
+            ParseError::MismatchedTypes(a) => a.diagnostic(),
+            ParseError::RecursionLimit(a) => a.diagnostic(),
+            ParseError::Lifetime(a) => a.diagnostic(),
+            ParseError::ReturnLocalReference(a) => a.diagnostic(),
+            ParseError::InvalidOperand(a) => a.diagnostic(),
+            ParseError::Mutability(a) => a.diagnostic(),
+            ParseError::Field(a) => a.diagnostic(),
+            ParseError::Index(a) => a.diagnostic(),
+            ParseError::MoveOut(a) => a.diagnostic(),
+            ParseError::Method(a) => a.diagnostic(),
+            ParseError::BorrowedMove(a) => a.diagnostic(),
+            ParseError::Copy(a) => a.diagnostic(),
+            ParseError::MoveBehindReference(a) => a.diagnostic(),
+            ParseError::BorrowedAccess(a) => a.diagnostic(),
+            ParseError::RecursiveType(a) => a.diagnostic(),
+        };
+        diagnostic.with_code(self.code())
+    }
+
+    /// 列挙子が持つエラーを返します。
+    fn inner(&self) -> &(dyn Error + 'static) {
+        match self {
+            ParseError::VariableNotFound(a) => a,
+            ParseError::Bracket(a) => a,
+            ParseError::InvalidExpression(a) => a,
+            ParseError::Operation(a) => a,
+            ParseError::MovedValue(a) => a,
+            ParseError::Borrow(a) => a,
+            ParseError::DanglingReference(a) => a,
+            ParseError::Reference(a) => a,
+            ParseError::MismatchedTypes(a) => a,
+            ParseError::RecursionLimit(a) => a,
+            ParseError::Lifetime(a) => a,
+            ParseError::ReturnLocalReference(a) => a,
+            ParseError::InvalidOperand(a) => a,
+            ParseError::Mutability(a) => a,
+            ParseError::Field(a) => a,
+            ParseError::Index(a) => a,
+            ParseError::MoveOut(a) => a,
+            ParseError::Method(a) => a,
+            ParseError::BorrowedMove(a) => a,
+            ParseError::Copy(a) => a,
+            ParseError::MoveBehindReference(a) => a,
+            ParseError::BorrowedAccess(a) => a,
+            ParseError::RecursiveType(a) => a,
+        }
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[{}] {}", self.code(), self.inner())
+    }
+}
+
+impl Error for ParseError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(self.inner())
+    }
+}
+
+macro_rules! impl_from_error {
+    ($($variant: ident($error: ty)),* $(,)?) => {
+        $(
+            impl From<$error> for ParseError {
+                fn from(value: $error) -> Self {
+                    ParseError::$variant(value)
+                }
+            }
+        )*
+    };
+}
+
+impl_from_error! {
+    VariableNotFound(VariableNotFoundError),
+    Bracket(BracketError),
+    InvalidExpression(InvalidExpressionError),
+    Operation(OperationError),
+    MovedValue(MovedValueError),
+    Borrow(BorrowError),
+    DanglingReference(DanglingReferenceError),
+    Reference(ReferenceError),
+    MismatchedTypes(MismatchedTypesError),
+    RecursionLimit(RecursionLimitError),
+    Lifetime(LifetimeError),
+    ReturnLocalReference(ReturnLocalReferenceError),
+    InvalidOperand(InvalidOperandError),
+    Mutability(MutabilityError),
+    Field(FieldError),
+    Index(IndexError),
+    MoveOut(MoveOutError),
+    Method(MethodError),
+    BorrowedMove(BorrowedMoveError),
+    Copy(CopyError),
+    MoveBehindReference(MoveBehindReferenceError),
+    BorrowedAccess(BorrowedAccessError),
+    RecursiveType(RecursiveTypeError),
+}
+
+/// エラーを `ParseError` に変換して `Result` 列挙型に入れたものを返すマクロです。
 #[macro_export]
 macro_rules! ret_err {
     ($x: expr) => {
         {
-            log::error!("{}", $x);
-            return Err(Box::new($x));
+            let error = $crate::parser::errors::ParseError::from($x);
+            log::error!("{}", error);
+            return Err(error);
         }
     };
 }
@@ -0,0 +1,493 @@
+use std::rc::Rc;
+
+use log::trace;
+
+use crate::ret_err;
+
+use super::ExprParser;
+use super::ast::{Block, BUILTIN_DROP, Expression, ExpressionKind, Statement};
+use super::borrow::BorrowKind;
+use super::errors::{FieldError, IndexError, MethodError, MismatchedTypesError, OperationError, ParseError, RecursionLimitError, ReferenceError, VariableNotFoundError};
+use super::token::Span;
+use super::variables::{Frame, Place, Reference, TEMPORARY_NAME, VarType};
+
+/// 評価の流れを定義します。
+#[derive(Debug, Default)]
+pub enum Flow {
+    /// 次の文へ進むことを表します。
+    #[default]
+    Normal,
+    /// `break` によりループを抜けることを表します。
+    Break(VarType),
+    /// `continue` により次の繰り返しへ移ることを表します。
+    Continue,
+    /// `return` により関数を抜けることを表します。
+    Return(VarType),
+}
+
+/// 関数呼び出しの深さの上限です。
+const MAX_CALL_DEPTH: usize = 256;
+
+impl ExprParser {
+    /// ブロックを評価します。ブロック内で宣言された変数はブロックの終わりで破棄されます。
+    /// `break` や `continue` が評価された場合は、残りの文を評価せずにブロックを抜けます。
+    /// * `block` - 評価するブロック
+    pub fn evaluate_block(&mut self, block: &Block) -> Result<VarType, ParseError> {
+        trace!("Start evaluating block at {}.", block.span);
+        self.push_scope();
+        for a in &block.statements {
+            match a {
+                Statement::Let(d, initializer) => {
+                    self.create_variable(d);
+                    if let Some(a) = initializer {
+                        self.evaluate(a)?;
+                    }
+                },
+                Statement::Debug(e) => {
+                    let value = self.evaluate(e)?;
+                    self.print(format!("{:?}", value));
+                },
+                Statement::Expression(e) => {
+                    // 文の値は使用されずにその場で破棄される
+                    let value = self.evaluate(e)?;
+                    self.drop_value(TEMPORARY_NAME, value);
+                },
+            }
+            if self.is_diverging() {
+                break;
+            }
+        }
+        let mut value = match &block.tail {
+            Some(a) if !self.is_diverging() => self.evaluate(a)?,
+            _ => VarType::Void,
+        };
+        self.drop_scope(Some(&mut value));
+        Ok(value)
+    }
+
+    /// `break`、`continue`、`return` により、評価の途中で抜けている最中であるかを取得します。
+    fn is_diverging(&self) -> bool {
+        !matches!(self.flow, Flow::Normal)
+    }
+
+    /// 式を評価します。
+    /// * `expression` - 評価する式
+    fn evaluate(&mut self, expression: &Expression) -> Result<VarType, ParseError> {
+        trace!("Evaluating {} at {}.", expression.id, expression.span);
+        match &expression.kind {
+            ExpressionKind::Integer(i) => Ok(VarType::Integer(*i)),
+            ExpressionKind::String(s) => Ok(VarType::String(s.clone())),
+            ExpressionKind::Variable(_) => {
+                let place = self.evaluate_place(expression, false)?;
+                Ok(self.read_place(&place, expression.id))
+            },
+            ExpressionKind::Block(b) => self.evaluate_block(b),
+            ExpressionKind::Reference(kind, e) => {
+                let place = if e.is_place() {
+                    let place = self.evaluate_place(e, *kind == BorrowKind::Mutable)?;
+                    if self.value_at(&place).is_empty() {
+                        ret_err!(OperationError::new(e.span))
+                    }
+                    place
+                } else {
+                    let value = self.evaluate(e)?;
+                    if value.is_empty() {
+                        ret_err!(OperationError::new(e.span))
+                    }
+                    self.allocate_temporary(value)
+                };
+                Ok(VarType::Pointer(Reference::new(place, *kind)))
+            },
+            // `Box` をムーブする参照外しは、元の場所から `Box` を取り除いて中の値を取り出す
+            ExpressionKind::Dereference(e) if self.moves.contains(&expression.id) => {
+                let place = self.evaluate_place(e, false)?;
+                match self.read_place(&place, expression.id) {
+                    VarType::Box(a) => Ok(*a),
+                    a => Self::calculate_monomial("*", a, expression.span),
+                }
+            },
+            ExpressionKind::Dereference(e) => match self.evaluate(e)? {
+                VarType::Pointer(p) => Ok(self.value_at(&p.place).clone()),
+                VarType::Box(a) => Ok(*a),
+                a => Self::calculate_monomial("*", a, expression.span),
+            },
+            ExpressionKind::Box(e) => Ok(VarType::Box(Box::new(self.evaluate(e)?))),
+            ExpressionKind::Unary(op, e) => {
+                let value = self.evaluate(e)?;
+                Self::calculate_monomial(op, value, expression.span)
+            },
+            ExpressionKind::Binary(op, l, r) => {
+                let left = self.evaluate(l)?;
+                if self.is_diverging() {
+                    return Ok(VarType::Void);
+                }
+                // 論理演算子は左辺で結果が決まる場合に右辺を評価しない
+                match (op.as_str(), &left) {
+                    ("&&", VarType::Integer(0)) => return Ok(VarType::Integer(0)),
+                    ("||", VarType::Integer(a)) if *a != 0 => return Ok(VarType::Integer(1)),
+                    _ => {},
+                }
+                let right = self.evaluate(r)?;
+                if self.is_diverging() {
+                    return Ok(VarType::Void);
+                }
+                Self::calculate_binomial(op, left, right, expression.span)
+            },
+            ExpressionKind::Assign(op, target, value) => {
+                let value = self.evaluate(value)?;
+                if self.is_diverging() {
+                    return Ok(VarType::Void);
+                }
+                let place = self.evaluate_place(target, true)?;
+                let binding = self.value_at_mut(&place);
+                if op == "=" {
+                    // 上書きされる値は代入の時点で破棄される
+                    let old = std::mem::replace(binding, value);
+                    self.drop_value(&place.name, old);
+                    Ok(VarType::Void)
+                } else {
+                    Self::calculate_assign(op, binding, value, expression.span)
+                }
+            },
+            ExpressionKind::If(condition, then, otherwise) => match self.evaluate(condition)? {
+                VarType::Integer(0) => match otherwise {
+                    Some(a) => self.evaluate(a),
+                    None => Ok(VarType::Void),
+                },
+                VarType::Integer(_) => self.evaluate_block(then),
+                VarType::Uninitialized | VarType::Void => ret_err!(OperationError::new(condition.span)),
+                a => ret_err!(MismatchedTypesError::new("i32".to_string(), a.type_name(), condition.span)),
+            },
+            ExpressionKind::While(condition, body) => loop {
+                match self.evaluate(condition)? {
+                    VarType::Integer(0) => return Ok(VarType::Void),
+                    VarType::Integer(_) => {},
+                    VarType::Uninitialized | VarType::Void => ret_err!(OperationError::new(condition.span)),
+                    a => ret_err!(MismatchedTypesError::new("i32".to_string(), a.type_name(), condition.span)),
+                }
+                self.evaluate_block(body)?;
+                match std::mem::take(&mut self.flow) {
+                    Flow::Break(_) => return Ok(VarType::Void),
+                    Flow::Return(a) => {
+                        self.flow = Flow::Return(a);
+                        return Ok(VarType::Void);
+                    },
+                    Flow::Normal | Flow::Continue => {},
+                }
+            },
+            ExpressionKind::Loop(body) => loop {
+                self.evaluate_block(body)?;
+                match std::mem::take(&mut self.flow) {
+                    Flow::Break(a) => return Ok(a),
+                    Flow::Return(a) => {
+                        self.flow = Flow::Return(a);
+                        return Ok(VarType::Void);
+                    },
+                    Flow::Normal | Flow::Continue => {},
+                }
+            },
+            ExpressionKind::Break(value) => {
+                let value = match value {
+                    Some(a) => self.evaluate(a)?,
+                    None => VarType::Void,
+                };
+                if !self.is_diverging() {
+                    self.flow = Flow::Break(value);
+                }
+                Ok(VarType::Void)
+            },
+            ExpressionKind::Continue => {
+                self.flow = Flow::Continue;
+                Ok(VarType::Void)
+            },
+            ExpressionKind::Call(name, arguments) if name == BUILTIN_DROP && !self.functions.contains_key(name) => self.call_drop(&arguments[0]),
+            ExpressionKind::Call(name, arguments) => {
+                let mut values = Vec::new();
+                for a in arguments {
+                    values.push(self.evaluate(a)?);
+                    if self.is_diverging() {
+                        return Ok(VarType::Void);
+                    }
+                }
+                self.call(name, values, expression.span)
+            },
+            ExpressionKind::Return(value) => {
+                let value = match value {
+                    Some(a) => self.evaluate(a)?,
+                    None => VarType::Void,
+                };
+                if !self.is_diverging() {
+                    self.flow = Flow::Return(value);
+                }
+                Ok(VarType::Void)
+            },
+            ExpressionKind::Struct(name, fields) => {
+                let mut values = Vec::new();
+                for a in fields {
+                    values.push((a.name.clone(), self.evaluate(&a.value)?));
+                    if self.is_diverging() {
+                        return Ok(VarType::Void);
+                    }
+                }
+                // フィールドは書かれた順に評価し、定義された順に格納する
+                let structs = Rc::clone(&self.structs);
+                values.sort_by_key(|(a, _)| structs[name].field(a).map(|(i, _)| i));
+                Ok(VarType::Struct(name.clone(), values))
+            },
+            ExpressionKind::Field(e, name, span) => {
+                if expression.is_place() {
+                    let place = self.evaluate_place(expression, false)?;
+                    return Ok(self.read_place(&place, expression.id));
+                }
+                let mut value = self.evaluate(e)?;
+                if self.is_diverging() {
+                    return Ok(VarType::Void);
+                }
+                // 参照のフィールドは参照先のフィールドとする
+                while let VarType::Pointer(p) = value {
+                    value = self.value_at(&p.place).clone();
+                }
+                match value.field_index(name) {
+                    Some(i) => match value {
+                        VarType::Struct(_, mut fields) => Ok(fields.swap_remove(i).1),
+                        VarType::Tuple(mut elements) => Ok(elements.swap_remove(i)),
+                        _ => unreachable!(),
+                    },
+                    None => ret_err!(FieldError::no_field(name.clone(), value.type_name(), *span)),
+                }
+            },
+            ExpressionKind::Tuple(elements) | ExpressionKind::Array(elements) | ExpressionKind::Vec(elements) => {
+                let mut values = Vec::new();
+                for a in elements {
+                    values.push(self.evaluate(a)?);
+                    if self.is_diverging() {
+                        return Ok(VarType::Void);
+                    }
+                }
+                match expression.kind {
+                    ExpressionKind::Tuple(_) if values.is_empty() => Ok(VarType::Void),
+                    ExpressionKind::Tuple(_) => Ok(VarType::Tuple(values)),
+                    ExpressionKind::Array(_) => Ok(VarType::Array(values)),
+                    _ => Ok(VarType::Vec(values)),
+                }
+            },
+            ExpressionKind::Index(e, index) => {
+                if expression.is_place() {
+                    let place = self.evaluate_place(expression, false)?;
+                    return Ok(self.read_place(&place, expression.id));
+                }
+                let mut value = self.evaluate(e)?;
+                if self.is_diverging() {
+                    return Ok(VarType::Void);
+                }
+                // 参照の要素は参照先の要素とする
+                while let VarType::Pointer(p) = value {
+                    value = self.value_at(&p.place).clone();
+                }
+                let i = self.evaluate_index(&value, index, expression.span)?;
+                match value {
+                    VarType::Array(mut elements) | VarType::Vec(mut elements) => Ok(elements.swap_remove(i)),
+                    _ => Ok(VarType::Void),
+                }
+            },
+            ExpressionKind::MethodCall(receiver, name, span, arguments) => self.evaluate_method_call(receiver, name, *span, arguments, expression.span),
+        }
+    }
+
+    /// 関数を呼び出します。呼び出し中は呼び出し元の変数を参照できません。
+    /// * `name` - 関数名
+    /// * `arguments` - 引数の値
+    /// * `span` - 関数呼び出しの範囲
+    fn call(&mut self, name: &str, arguments: Vec<VarType>, span: Span) -> Result<VarType, ParseError> {
+        if self.frames.len() >= MAX_CALL_DEPTH {
+            ret_err!(RecursionLimitError::new(name.to_string(), span))
+        }
+        let functions = Rc::clone(&self.functions);
+        let function = &functions[name];
+        trace!("Calling function \"{}\".", name);
+        self.frames.push(Frame { function: name.to_string(), base: self.scopes.len() });
+        self.push_scope();
+        for (parameter, value) in function.parameters.iter().zip(arguments) {
+            self.create_variable(parameter);
+            let slot = self.get_slot(parameter.binding).unwrap();
+            self.slots[slot].value = value;
+        }
+        let mut result = self.evaluate_block(&function.body);
+        self.drop_scope(result.as_mut().ok());
+        if let Some(a) = self.frames.pop() {
+            trace!("Returned from function \"{}\".", a.function);
+        }
+        let value = result?;
+        match std::mem::take(&mut self.flow) {
+            Flow::Return(a) => Ok(a),
+            _ => Ok(value),
+        }
+    }
+
+    /// 組み込みの関数 `drop` を呼び出します。引数の値はムーブされ、その場で破棄されます。
+    /// * `argument` - 破棄する値の式
+    fn call_drop(&mut self, argument: &Expression) -> Result<VarType, ParseError> {
+        let (name, value) = if argument.is_place() {
+            let place = self.evaluate_place(argument, false)?;
+            (place.name.clone(), self.read_place(&place, argument.id))
+        } else {
+            (TEMPORARY_NAME.to_string(), self.evaluate(argument)?)
+        };
+        if !self.is_diverging() {
+            self.drop_value(&name, value);
+        }
+        Ok(VarType::Void)
+    }
+
+    /// 場所に格納されている値を読み出します。値をムーブする式であれば、元の場所から値を取り除きます。
+    /// * `place` - 値の場所
+    /// * `id` - 値を読み出す式の番号
+    fn read_place(&mut self, place: &Place, id: usize) -> VarType {
+        if self.moves.contains(&id) {
+            trace!("Value of \"{}\" was moved.", place.name);
+            std::mem::replace(self.value_at_mut(place), VarType::Uninitialized)
+        } else {
+            self.value_at(place).clone()
+        }
+    }
+
+    /// メソッド呼び出しを評価します。受け取り側の値、引数の順に評価し、参照の受け取り側は参照先をたどります。
+    /// * `receiver` - 受け取り側の式
+    /// * `name` - メソッド名
+    /// * `name_span` - メソッド名の範囲
+    /// * `arguments` - 引数の式
+    /// * `span` - メソッド呼び出しの範囲
+    fn evaluate_method_call(&mut self, receiver: &Expression, name: &str, name_span: Span, arguments: &[Expression], span: Span) -> Result<VarType, ParseError> {
+        // `push` と `pop` は値を変更するため、可変として借用する
+        let mutable = !matches!(name, "len" | "clone");
+        let mut place = if receiver.is_place() {
+            self.evaluate_place(receiver, mutable)?
+        } else {
+            let value = self.evaluate(receiver)?;
+            self.allocate_temporary(value)
+        };
+        let mut values = Vec::new();
+        for a in arguments {
+            values.push(self.evaluate(a)?);
+            if self.is_diverging() {
+                return Ok(VarType::Void);
+            }
+        }
+        // 参照のメソッドは参照先のメソッドとする
+        while let VarType::Pointer(p) = self.value_at(&place) {
+            if mutable && p.kind == BorrowKind::Shared {
+                ret_err!(ReferenceError::borrow_behind_shared_reference(receiver.span))
+            }
+            place = p.place.clone();
+        }
+        self.call_method(&place, name, values, name_span, span)
+    }
+
+    /// 組み込みのメソッドを呼び出します。
+    /// * `place` - メソッドを呼び出す値の場所
+    /// * `name` - メソッド名
+    /// * `arguments` - 引数の値
+    /// * `name_span` - メソッド名の範囲
+    /// * `span` - メソッド呼び出しの範囲
+    fn call_method(&mut self, place: &Place, name: &str, mut arguments: Vec<VarType>, name_span: Span, span: Span) -> Result<VarType, ParseError> {
+        trace!("Calling method \"{}\" of \"{}\".", name, place.name);
+        match (self.value_at_mut(place), name) {
+            (VarType::Vec(a), "push") => {
+                a.push(arguments.remove(0));
+                Ok(VarType::Void)
+            },
+            (VarType::Vec(a), "pop") => match a.pop() {
+                Some(b) => Ok(b),
+                None => ret_err!(IndexError::empty(span)),
+            },
+            (VarType::Vec(a), "len") => Ok(VarType::Integer(a.len() as i32)),
+            (VarType::Uninitialized | VarType::Void, _) => ret_err!(OperationError::new(span)),
+            (a, "clone") => Ok(a.clone()),
+            (a, _) => ret_err!(MethodError::new(name.to_string(), a.type_name(), name_span)),
+        }
+    }
+
+    /// 添字を評価し、要素の位置を返します。範囲外であればエラーを返します。
+    /// * `value` - アクセスする値
+    /// * `index` - 添字の式
+    /// * `span` - 添字によるアクセスの範囲
+    fn evaluate_index(&mut self, value: &VarType, index: &Expression, span: Span) -> Result<usize, ParseError> {
+        let len = match value {
+            VarType::Array(a) | VarType::Vec(a) => a.len(),
+            VarType::Uninitialized | VarType::Void => ret_err!(OperationError::new(span)),
+            a => ret_err!(IndexError::not_indexable(a.type_name(), span)),
+        };
+        match self.evaluate(index)? {
+            VarType::Integer(i) if usize::try_from(i).is_ok_and(|a| a < len) => Ok(i as usize),
+            VarType::Integer(i) => ret_err!(IndexError::out_of_bounds(len, i, span)),
+            VarType::Uninitialized | VarType::Void => ret_err!(OperationError::new(index.span)),
+            a => ret_err!(MismatchedTypesError::new("i32".to_string(), a.type_name(), index.span)),
+        }
+    }
+
+    /// 式が表す場所を取得します。
+    /// * `expression` - 変数、参照外し、フィールドまたは要素の式
+    /// * `mutable` - 場所を変更するために取得するか
+    fn evaluate_place(&mut self, expression: &Expression, mutable: bool) -> Result<Place, ParseError> {
+        match &expression.kind {
+            ExpressionKind::Variable(v) => match self.get_slot(v.binding) {
+                Some(slot) => Ok(Place { slot, name: v.name.clone(), path: Vec::new() }),
+                None => ret_err!(VariableNotFoundError::new(v.name.clone(), expression.span)),
+            },
+            ExpressionKind::Dereference(e) => {
+                let (value, place) = if e.is_place() {
+                    let place = self.evaluate_place(e, false)?;
+                    (self.value_at(&place).clone(), Some(place))
+                } else {
+                    (self.evaluate(e)?, None)
+                };
+                match (value, place) {
+                    (VarType::Pointer(p), _) if mutable && p.kind == BorrowKind::Shared => ret_err!(ReferenceError::behind_shared_reference(expression.span)),
+                    (VarType::Pointer(p), _) => Ok(p.place),
+                    // `Box` の参照外しは、`Box` が所有する値の場所とする
+                    (VarType::Box(_), Some(mut place)) => {
+                        place.path.push(0);
+                        place.name = format!("*{}", place.name);
+                        Ok(place)
+                    },
+                    (VarType::Box(a), None) => Ok(self.allocate_temporary(*a)),
+                    _ => ret_err!(ReferenceError::invalid_dereference(e.span)),
+                }
+            },
+            ExpressionKind::Field(e, name, span) => {
+                let mut place = self.evaluate_place(e, mutable)?;
+                // 参照のフィールドは参照先のフィールドとする
+                while let VarType::Pointer(p) = self.value_at(&place) {
+                    if mutable && p.kind == BorrowKind::Shared {
+                        ret_err!(ReferenceError::behind_shared_reference(expression.span))
+                    }
+                    place = p.place.clone();
+                }
+                let value = self.value_at(&place);
+                let index = match value.field_index(name) {
+                    Some(i) => i,
+                    None => ret_err!(FieldError::no_field(name.clone(), value.type_name(), *span)),
+                };
+                place.path.push(index);
+                place.name = format!("{}.{}", place.name, name);
+                Ok(place)
+            },
+            ExpressionKind::Index(e, index) => {
+                let mut place = self.evaluate_place(e, mutable)?;
+                // 参照の要素は参照先の要素とする
+                while let VarType::Pointer(p) = self.value_at(&place) {
+                    if mutable && p.kind == BorrowKind::Shared {
+                        ret_err!(ReferenceError::behind_shared_reference(expression.span))
+                    }
+                    place = p.place.clone();
+                }
+                let value = self.value_at(&place).clone();
+                let i = self.evaluate_index(&value, index, expression.span)?;
+                place.path.push(i);
+                place.name = format!("{}[{}]", place.name, i);
+                Ok(place)
+            },
+            _ => ret_err!(ReferenceError::invalid_dereference(expression.span)),
+        }
+    }
+}
@@ -0,0 +1,453 @@
+/// エラーコードと詳しい説明の一覧です。
+const EXPLANATIONS: [(&str, &str); 34] = [
+    ("E0001", r#"A variable, function or type was used that is not declared in any enclosing
+scope.
+
+Erroneous code example:
+
+    let a = 1;
+    debug a + b; // `b` was never declared
+    f(a);        // `f` was never defined
+
+Declare the variable with `let` before using it, and make sure the block that
+declared it has not ended yet. Functions are defined with `fn` and can be
+called from anywhere in the file, but the body of a function cannot use
+variables declared outside of it.
+
+A type annotation naming an unknown type, such as `let c: Foo = 1;`, is
+reported with the same code. The types that can be written in annotations are
+`i32`, `String` (or `str`), structs defined with `struct`, tuples such as
+`(i32, String)`, arrays such as `[i32; 3]`, `Vec<T>`, `Box<T>` and references
+to any of them such as `&i32` or `&mut Vec<i32>`.
+"#),
+    ("E0002", r#"A bracket has no corresponding opening or closing bracket.
+
+Erroneous code example:
+
+    let a; a = (1 + 2;
+
+Every `(` must be closed by `)`, every `[` by `]` and every `{` by `}`, and
+they must not cross each other as in `{ ( } )`.
+"#),
+    ("E0003", r#"The input could not be parsed as an expression.
+
+Erroneous code example:
+
+    let a; a = 1 +;
+
+Check the place shown in the message. Typical causes are a missing operand, an
+operator that does not exist, or assigning to something that is not a variable.
+"#),
+    ("E0004", r#"An operation was performed on a value that is void.
+
+Erroneous code example:
+
+    fn log(n) { debug n; } // the body ends with `;`, so the call is void
+    log(1) + 1
+
+Make sure the expression produces a value, for example by removing the `;`
+after the last expression of a block.
+
+This error is found while the program runs. When the operand is known to be
+void before running, for example a block without a tail expression, E0017 is
+reported instead.
+"#),
+    ("E0005", r#"A value was used after it had been moved.
+
+Erroneous code example:
+
+    let a; a = "hello";
+    let b; b = a; // the string is moved into `b`
+    a + "!"       // error: `a` no longer owns a value
+
+Strings are moved when they are assigned, so the old variable cannot be used
+any more. The same holds for mutable references, boxes, vectors and structs,
+while integers, shared references and structs marked with `#[derive(Copy)]`
+are `Copy` and are copied instead. Inside a loop, a value moved in one
+iteration is also gone in the next iteration, which is reported as "value
+moved here, in previous iteration of loop". Borrow the value with `&a`
+instead, move a copy made with `a.clone()`, or assign a new value to `a`
+before using it again. This corresponds to rustc's E0382.
+"#),
+    ("E0006", r#"A variable was mutably borrowed more than once at the same time.
+
+Erroneous code example:
+
+    let mut a = 1;
+    let p; p = &mut a;
+    let q; q = &mut a; // error: `p` is still used below
+    *p = 2;
+
+Only one mutable reference to a value may be live at a time. A borrow stays
+live until the last use of the variable holding it. This corresponds to
+rustc's E0499.
+"#),
+    ("E0007", r#"A variable was borrowed as mutable while it was also borrowed as immutable,
+or the other way around.
+
+Erroneous code example:
+
+    let mut a = 1;
+    let p; p = &a;
+    let q; q = &mut a; // error: `p` is still used below
+    *p
+
+Any number of shared references may coexist, but not together with a mutable
+one. Finish using the shared references before creating the mutable one. This
+corresponds to rustc's E0502.
+"#),
+    ("E0008", r#"A reference to a variable outlives the block that declares the variable.
+
+Erroneous code example:
+
+    let r;
+    {
+        let x; x = 5;
+        r = &x; // error: `x` is dropped at the end of the block
+    }
+    *r
+
+Variables are dropped at the end of their block, so a reference to them must
+not be used after it. Declare the variable in the outer block instead. This
+corresponds to rustc's E0597.
+"#),
+    ("E0009", r#"A value that is not a reference was dereferenced or used as a place.
+
+Erroneous code example:
+
+    let a; a = 1;
+    *a
+
+Only references created with `&` or `&mut` can be dereferenced with `*`.
+"#),
+    ("E0010", r#"A variable was used before a value was assigned to it.
+
+Erroneous code example:
+
+    let a;
+    if 1 { a = 1; }
+    a + 1 // error: `a` is not assigned when the condition is false
+
+Variables are checked before the program runs. A variable must be assigned on
+every path that reaches a use of it, including both branches of an `if` and
+the case where a `while` loop runs zero times. Assigning inside a `loop` counts
+only for the paths that reach a `break` after the assignment. This corresponds
+to rustc's E0381.
+"#),
+    ("E0011", r#"A value behind a shared reference was modified.
+
+Erroneous code example:
+
+    let a; a = 1;
+    let p; p = &a;
+    *p = 2; // error: `p` is a shared reference
+
+Borrow the value with `&mut` to modify it through the reference. Calling a
+method that modifies the value, such as `push` on a `Vec`, through a shared
+reference is reported with the same code. This corresponds to rustc's E0594,
+and to E0596 for a method call.
+"#),
+    ("E0012", r#"A value of an unexpected type was used.
+
+Erroneous code example:
+
+    let s: String = "yes";
+    if s { 1 } else { 2 } // error: the condition must be an integer
+    let mut n = 5;        // the type of `n` is inferred as `i32` here
+    n = s;                // error: `s` is a `String`, not an `i32`
+
+Types are checked before the program runs. A value must have the type written
+in the annotation of the variable, argument or return value it is stored in.
+A variable without an annotation gets the type of the first value it is used
+with, and every later use must agree with it; the message points at the value
+the type was inferred from.
+Conditions of `if` must be integers, where 0 is false and any other value is
+true, and both branches of an `if` must produce values of the same type. This
+corresponds to rustc's E0308.
+"#),
+    ("E0013", r#"Too many function calls were nested while running the program.
+
+Erroneous code example:
+
+    fn f(n) { f(n + 1) } // never stops calling itself
+    f(0)
+
+Make sure that every recursive function has a condition under which it
+returns without calling itself again.
+"#),
+    ("E0014", r#"A function returns a reference, but it cannot be decided which argument the
+reference is borrowed from.
+
+Erroneous code example:
+
+    fn longest(x: &i32, y: &i32) -> &i32 { x }
+
+When the lifetime of a returned reference is omitted, it is taken from the
+arguments using the elision rules: every omitted lifetime of an argument is a
+different lifetime, and if the arguments have exactly one lifetime, that
+lifetime is used for the return value. Otherwise, declare a lifetime parameter
+and write it on the arguments the result may borrow from:
+
+    fn longest<'a>(x: &'a i32, y: &'a i32) -> &'a i32 { x }
+
+This corresponds to rustc's E0106.
+"#),
+    ("E0015", r#"A lifetime was used in a function signature without being declared.
+
+Erroneous code example:
+
+    fn first<'a>(x: &'a i32, y: &'b i32) -> &'a i32 { x } // `'b` is not declared
+
+Declare every lifetime in the angle brackets after the function name, as in
+`fn first<'a, 'b>(...)`. Only `'static` can be used without a declaration.
+This corresponds to rustc's E0261.
+"#),
+    ("E0016", r#"A function returns a reference to one of its own variables or arguments.
+
+Erroneous code example:
+
+    fn make() -> &'static i32 {
+        let n; n = 1;
+        &n // `n` is dropped when the function returns
+    }
+
+Every variable and argument of a function is dropped when the function
+returns, so a reference to it would be left dangling. Return the value itself
+instead, or return a reference that was passed in as an argument. This
+corresponds to rustc's E0515.
+"#),
+    ("E0017", r#"An operator was used with a value of a type it does not support.
+
+Erroneous code example:
+
+    let s: String = "a";
+    let n = s - 1; // `-` only works on integers
+    let m = -s;    // so does unary `-`
+
+`+` can add two integers or concatenate two strings. All other arithmetic,
+bitwise, shift, comparison and logical operators only work on integers. Use
+`*` to get the value behind a reference first. This corresponds to rustc's
+E0369 and E0600.
+"#),
+    ("E0018", r#"A variable that is not declared as mutable was assigned more than once.
+
+Erroneous code example:
+
+    let x = 1;
+    x = 2; // `x` already holds a value
+
+A binding declared without `mut` can be assigned only once, either by its
+initializer or by a later assignment such as `let x; x = 1;`. Declare the
+binding as `let mut x` to allow it to change. Function arguments are
+immutable as well unless they are written as `mut x: i32`. This corresponds to
+rustc's E0384.
+"#),
+    ("E0019", r#"A variable that is not declared as mutable was borrowed mutably.
+
+Erroneous code example:
+
+    let x = 1;
+    let r = &mut x; // `x` is not `mut`
+
+A mutable reference allows the value to change, so the borrowed variable must
+be declared with `let mut`. Borrow it with `&` if it only needs to be read.
+This corresponds to rustc's E0596.
+"#),
+    ("E0020", r#"A field that the struct does not declare was accessed or initialized.
+
+Erroneous code example:
+
+    struct Point { x: i32, y: i32 }
+    let p = Point { x: 1, y: 2 };
+    p.z; // `Point` has no field `z`
+
+Only the fields written in the struct declaration exist. Check the spelling of
+the field name and the type of the value it is accessed on. This corresponds to
+rustc's E0609 and E0560.
+"#),
+    ("E0021", r#"A struct literal did not specify every field of the struct.
+
+Erroneous code example:
+
+    struct Point { x: i32, y: i32 }
+    let p = Point { x: 1 }; // `y` is missing
+
+Every field must be given a value when the struct is created. This corresponds
+to rustc's E0063.
+"#),
+    ("E0022", r#"A field of a variable that is not declared as mutable was assigned.
+
+Erroneous code example:
+
+    struct Point { x: i32, y: i32 }
+    let p = Point { x: 1, y: 2 };
+    p.x = 3; // `p` is not `mut`
+
+Fields are mutable only when the variable holding the struct is. Declare it
+with `let mut p` to change its fields. The same applies to the value owned by
+a `Box`, as in `*b = 3`. This corresponds to rustc's E0594.
+"#),
+    ("E0023", r#"A value whose type cannot be indexed was indexed.
+
+Erroneous code example:
+
+    let t = (1, 2);
+    t[0]; // tuples are not indexed with `[]`
+
+Only arrays can be indexed with `a[i]`. Access the elements of a tuple with
+`t.0`, `t.1` and so on. This corresponds to rustc's E0608.
+"#),
+    ("E0024", r#"An array was indexed with an index outside of its length.
+
+Erroneous code example:
+
+    let a = [1, 2, 3];
+    let i = 3;
+    a[i]; // the len is 3 but the index is 3
+
+The index must be at least 0 and less than the length of the array. The
+length of an array is fixed by its type, as in `[i32; 3]`, while the length of
+a `Vec` is the number of elements it holds at that point. This error is
+reported while running the program, where Rust would panic.
+"#),
+    ("E0025", r#"An element of an array was moved out by indexing.
+
+Erroneous code example:
+
+    let a = ["x", "y"];
+    let s = a[0]; // moves a `String` out of the array
+
+Moving one element would leave a hole in the array, so elements whose type is
+not `Copy` can only be borrowed, as in `&a[0]`. Elements such as `i32` are
+copied instead. The same applies to the elements of a `Vec`, where an element
+can be taken out with `pop` instead. This corresponds to rustc's E0508, and to
+E0507 for a `Vec`.
+"#),
+    ("E0026", r#"A method was called that does not exist for the type of the value.
+
+Erroneous code example:
+
+    let a = [1, 2, 3];
+    a.push(4); // arrays have a fixed length
+
+A `Vec` has the methods `push`, which appends an element, `pop`, which
+removes and returns the last element, and `len`, which returns the number of
+elements. Every value except `()` has `clone`, which returns a copy of the
+value. Methods of the value behind a reference can be called through the
+reference. This corresponds to rustc's E0599.
+"#),
+    ("E0027", r#"A value was moved while it was still borrowed.
+
+Erroneous code example:
+
+    let a = Box::new(1);
+    let r = &a;
+    drop(a); // `a` is moved into `drop` while `r` still borrows it
+    debug **r;
+
+Moving a value invalidates every reference to it, so it can only be moved
+after the last use of those references. The same applies to passing it to
+`drop`. This corresponds to rustc's E0505.
+"#),
+    ("E0028", r#"A struct with a field that is not `Copy` was marked with `#[derive(Copy)]`.
+
+Erroneous code example:
+
+    #[derive(Clone, Copy)]
+    struct Named { id: i32, name: String } // `String` is not `Copy`
+
+Copying a struct copies each of its fields, so every field must be `Copy`
+itself: integers, shared references, tuples and arrays of `Copy` values, and
+other structs marked with `#[derive(Copy)]`. Remove the marker to move the
+struct instead. This corresponds to rustc's E0204.
+"#),
+    ("E0029", r#"A value was moved out from behind a reference.
+
+Erroneous code example:
+
+    let s = "x";
+    let r = &s;
+    let t = *r; // moves the `String` that `s` owns out through `r`
+
+A reference only borrows the value, so the value behind it cannot be moved
+out, whether through `*` or through a field as in `r.name`. Values that are
+`Copy`, such as `i32`, are copied instead. Borrow the value, as in `&*r`, or
+call `clone` to make a copy of it. A value owned by a `Box` can be moved out
+with `*`, which moves the box itself. This corresponds to rustc's E0507.
+"#),
+    ("E0030", r#"A value was used while it was mutably borrowed.
+
+Erroneous code example:
+
+    let mut a = 1;
+    let r = &mut a;
+    let b = a; // `a` is read while `r` still borrows it mutably
+    *r = 2;
+
+A mutable reference has exclusive access to the value it borrows, so the value
+cannot be read through its own name until the last use of the reference. Read
+it through the reference instead, as in `*r`, or use the reference for the
+last time before reading the value. This corresponds to rustc's E0503.
+"#),
+    ("E0031", r#"A value was assigned to while it was borrowed.
+
+Erroneous code example:
+
+    let mut a = 1;
+    let r = &a;
+    a = 2; // `a` is assigned to while `r` still borrows it
+    debug *r;
+
+Assigning to a value, including with `+=` and the other compound assignment
+operators, would change what every reference to it sees, so it can only be
+done after the last use of those references. This also applies to fields and
+elements, and to the value replaced by `v = Vec::new()` while a reference to
+one of its elements is alive. This corresponds to rustc's E0506.
+"#),
+    ("E0032", r#"A function returns a reference with a lifetime other than the one declared for
+its return value.
+
+Erroneous code example:
+
+    fn first<'a, 'b>(x: &'a i32, y: &'b i32) -> &'a i32 {
+        y // `y` is only known to live for `'b`
+    }
+
+The caller relies on the signature alone: a reference returned as `&'a i32`
+is assumed to borrow only from the arguments with lifetime `'a`, and may be
+used after the other arguments are gone. Return a reference from an argument
+with the declared lifetime, or give the returned argument the same lifetime,
+as in `y: &'a i32`. This corresponds to rustc's "lifetime may not live long
+enough" error and E0621.
+"#),
+    ("E0033", r#"A struct contains itself without any indirection, so it would have infinite size.
+
+Erroneous code example:
+
+    struct Node { value: i32, next: Node }
+
+A struct stores its fields inline, so a struct that contains itself, directly
+or through other structs, tuples or arrays, could never be laid out. Store the
+recursive part behind a pointer instead, as in `next: Box<Node>` or
+`children: Vec<Node>`. This corresponds to rustc's E0072.
+"#),
+    ("E0034", r#"An element was popped from an empty `Vec`.
+
+Erroneous code example:
+
+    let mut v = vec![1];
+    v.pop();
+    v.pop(); // `v` has no elements left
+
+`pop` removes and returns the last element of a `Vec`, so the `Vec` must hold
+at least one element. Check `len` before calling `pop`, as in
+`if v.len() > 0 { v.pop(); }`. This error is reported while running the
+program; in Rust, `pop` returns an `Option` that is `None` for an empty `Vec`.
+"#),
+];
+
+/// エラーコードの詳しい説明を返します。存在しないコードの場合は `None` を返します。
+/// - `code` - 説明を取得するエラーコード
+pub fn explain(code: &str) -> Option<&'static str> {
+    EXPLANATIONS.iter().find(|(a, _)| a.eq_ignore_ascii_case(code)).map(|(_, a)| *a)
+}
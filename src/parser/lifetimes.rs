@@ -0,0 +1,168 @@
+use std::collections::HashSet;
+
+use log::info;
+
+use super::ast::{Function, Program, Struct, TypeKind};
+use super::errors::{LifetimeError, ParseError};
+use super::token::Span;
+
+/// どこからも借用しない参照の寿命です。宣言せずに使用できます。
+pub const STATIC_LIFETIME: &str = "'static";
+
+/// 引数の型に含まれる寿命を識別する列挙型です。
+/// 省略された寿命は、Rust の省略規則に従って参照ごとに異なる寿命とみなします。
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Region {
+    /// 名前の付いた寿命を表します。
+    Named(String),
+    /// 省略された寿命を、引数の位置と引数の型の中での参照の位置で表します。
+    Elided(usize, usize),
+}
+
+/// すべての構造体と関数の定義に含まれる寿命の指定を検査します。
+/// - `program` - 検査するプログラム
+pub fn check(program: &Program) -> Result<(), Vec<ParseError>> {
+    info!("Start lifetime checking...");
+    let mut structs: Vec<&Struct> = program.structs.values().collect();
+    structs.sort_by_key(|a| a.span.start);
+    let mut functions: Vec<&Function> = program.functions.values().collect();
+    functions.sort_by_key(|a| a.span.start);
+    let errors: Vec<ParseError> = structs.into_iter().flat_map(check_struct).chain(functions.into_iter().flat_map(check_function)).collect();
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+/// 構造体の定義に含まれる寿命の指定を検査します。
+/// 構造体は寿命引数を宣言できないため、フィールドには `'static` の参照のみを格納できます。
+/// - `definition` - 検査する構造体
+fn check_struct(definition: &Struct) -> Vec<ParseError> {
+    definition.fields
+        .iter()
+        .flat_map(|a| a.ty.references())
+        .filter_map(|(lifetime, span)| match lifetime {
+            None => Some(LifetimeError::in_struct(span).into()),
+            Some(a) if a.name != STATIC_LIFETIME => Some(LifetimeError::undeclared(a.name.clone(), a.span).into()),
+            Some(_) => None,
+        })
+        .collect()
+}
+
+/// 関数の定義に含まれる寿命の指定を検査します。
+/// 使用された寿命が宣言されていること、戻り値の省略された寿命が一つの引数の寿命に決まることを確認します。
+/// - `function` - 検査する関数
+fn check_function(function: &Function) -> Vec<ParseError> {
+    let mut errors = Vec::new();
+    let types = function.parameters.iter().filter_map(|a| a.ty.as_ref()).chain(function.output.as_ref());
+    for (lifetime, _) in types.flat_map(|a| a.references()) {
+        if let Some(a) = lifetime {
+            if a.name != STATIC_LIFETIME && !function.lifetimes.iter().any(|b| b.name == a.name) {
+                errors.push(LifetimeError::undeclared(a.name.clone(), a.span).into());
+            }
+        }
+    }
+    let Some(output) = &function.output else {
+        return errors;
+    };
+    let inputs = input_regions(function);
+    if distinct(&inputs).len() == 1 {
+        return errors;
+    }
+    let mut candidates: Vec<String> = Vec::new();
+    for (_, index) in &inputs {
+        let name = &function.parameters[*index].name;
+        if !candidates.contains(name) {
+            candidates.push(name.clone());
+        }
+    }
+    let references: Vec<Span> = function.parameters
+        .iter()
+        .filter_map(|a| a.ty.as_ref())
+        .filter(|a| !a.references().is_empty())
+        .map(|a| a.span)
+        .collect();
+    for (_, span) in output.references().into_iter().filter(|(a, _)| a.is_none()) {
+        errors.push(LifetimeError::missing(span, candidates.clone(), references.clone()).into());
+    }
+    errors
+}
+
+/// 関数の戻り値が借用を引き継ぐ引数の位置を返します。
+/// 戻り値の寿命と同じ寿命を持つ参照を受け取る引数の借用のみが戻り値に引き継がれます。
+/// 戻り値の型が省略されていれば `None` を返します。
+/// - `function` - 対象の関数
+pub fn output_sources(function: &Function) -> Option<Vec<usize>> {
+    let regions = output_regions(function)?;
+    let mut sources: Vec<usize> = input_regions(function).into_iter().filter(|(a, _)| regions.contains(a)).map(|(_, a)| a).collect();
+    sources.dedup();
+    Some(sources)
+}
+
+/// 関数の戻り値の型に含まれる参照の寿命を返します。省略された寿命は、引数の寿命から決まる寿命とします。
+/// `'static` はどの引数の寿命でもないため含めません。戻り値の型が省略されていれば `None` を返します。
+/// - `function` - 対象の関数
+pub fn output_regions(function: &Function) -> Option<HashSet<Region>> {
+    let output = function.output.as_ref()?;
+    let inputs = input_regions(function);
+    let elided = match distinct(&inputs).as_slice() {
+        [a] => Some((*a).clone()),
+        _ => None,
+    };
+    let mut regions = HashSet::new();
+    for (lifetime, _) in output.references() {
+        match lifetime {
+            Some(a) if a.name == STATIC_LIFETIME => {},
+            Some(a) => _ = regions.insert(Region::Named(a.name.clone())),
+            None => regions.extend(elided.clone()),
+        }
+    }
+    Some(regions)
+}
+
+/// 参照型の引数が受け取る参照の寿命と、寿命が書かれた範囲 (省略されていれば `&` の範囲) を返します。
+/// 引数の型が参照でなければ `None` を返します。
+/// - `function` - 対象の関数
+/// - `index` - 引数の位置
+pub fn parameter_region(function: &Function, index: usize) -> Option<(Region, Span)> {
+    let ty = function.parameters[index].ty.as_ref()?;
+    let TypeKind::Reference(..) = ty.kind else {
+        return None;
+    };
+    match ty.references()[0] {
+        (Some(a), _) => Some((Region::Named(a.name.clone()), a.span)),
+        (None, span) => Some((Region::Elided(index, 0), span)),
+    }
+}
+
+/// 引数の型に含まれる寿命と、その寿命を持つ引数の位置を列挙します。
+/// - `function` - 対象の関数
+fn input_regions(function: &Function) -> Vec<(Region, usize)> {
+    let mut regions = Vec::new();
+    for (index, parameter) in function.parameters.iter().enumerate() {
+        let Some(ty) = &parameter.ty else {
+            continue;
+        };
+        for (position, (lifetime, _)) in ty.references().into_iter().enumerate() {
+            let region = match lifetime {
+                Some(a) => Region::Named(a.name.clone()),
+                None => Region::Elided(index, position),
+            };
+            regions.push((region, index));
+        }
+    }
+    regions
+}
+
+/// 重複を除いた寿命の一覧を返します。
+/// - `regions` - 寿命と引数の位置の一覧
+fn distinct(regions: &[(Region, usize)]) -> Vec<&Region> {
+    let mut distinct: Vec<&Region> = Vec::new();
+    for (a, _) in regions {
+        if !distinct.contains(&a) {
+            distinct.push(a);
+        }
+    }
+    distinct
+}
@@ -0,0 +1,176 @@
+use std::collections::{HashMap, HashSet};
+
+use super::ast::{Block, Expression, ExpressionKind, Program, Statement};
+
+/// 変数の生存区間を解析した結果を保持する構造体です。
+/// 変数は、以降に上書きされるより前に読み出される可能性がある場合に生存しているとみなします。
+#[derive(Debug, Default)]
+pub struct Liveness {
+    /// 式の番号ごとの、式を評価した直後に生存している変数
+    live_after: HashMap<usize, HashSet<usize>>,
+    /// ループの番号ごとの、繰り返しの終わりかループの後で生存している変数
+    live_across: HashMap<usize, HashSet<usize>>,
+    /// 解析中のループの、ループの直後と繰り返しの先頭で生存している変数
+    loops: Vec<(HashSet<usize>, HashSet<usize>)>,
+}
+
+impl Liveness {
+    /// 構文木を後ろから走査して変数の生存区間を解析します。関数の本体はそれぞれ別に解析します。
+    /// - `program` - 解析するプログラム
+    pub fn analyze(program: &Program) -> Liveness {
+        let mut liveness = Liveness::default();
+        liveness.block(&program.main, HashSet::new());
+        for a in program.functions.values() {
+            liveness.block(&a.body, HashSet::new());
+        }
+        liveness
+    }
+
+    /// 変数が式を評価した直後に生存しているかを取得します。
+    /// - `id` - 式の番号
+    /// - `binding` - 変数を識別する番号
+    pub fn is_live_after(&self, id: usize, binding: usize) -> bool {
+        self.live_after.get(&id).is_some_and(|a| a.contains(&binding))
+    }
+
+    /// 式を評価した直後に生存している変数の一覧を取得します。
+    /// - `id` - 式の番号
+    pub fn live_after(&self, id: usize) -> &HashSet<usize> {
+        &self.live_after[&id]
+    }
+
+    /// ループの本体の終わりで生存している変数の一覧を取得します。
+    /// 次の繰り返しで使用される変数と、ループの後で使用される変数が含まれます。
+    /// - `id` - ループを表す式の番号
+    pub fn live_across(&self, id: usize) -> &HashSet<usize> {
+        &self.live_across[&id]
+    }
+
+    /// ブロックの直前で生存している変数を求めます。
+    /// - `block` - 解析するブロック
+    /// - `live` - ブロックの直後で生存している変数
+    fn block(&mut self, block: &Block, mut live: HashSet<usize>) -> HashSet<usize> {
+        if let Some(a) = &block.tail {
+            live = self.expression(a, live);
+        }
+        for a in block.statements.iter().rev() {
+            live = match a {
+                Statement::Let(d, initializer) => {
+                    if let Some(a) = initializer {
+                        live = self.expression(a, live);
+                    }
+                    live.remove(&d.binding);
+                    live
+                },
+                Statement::Debug(e) | Statement::Expression(e) => self.expression(e, live),
+            };
+        }
+        live
+    }
+
+    /// 式の直前で生存している変数を求めます。
+    /// - `expression` - 解析する式
+    /// - `live` - 式の直後で生存している変数
+    fn expression(&mut self, expression: &Expression, mut live: HashSet<usize>) -> HashSet<usize> {
+        self.live_after.insert(expression.id, live.clone());
+        match &expression.kind {
+            ExpressionKind::Integer(_) | ExpressionKind::String(_) => live,
+            ExpressionKind::Variable(v) => {
+                live.insert(v.binding);
+                live
+            },
+            ExpressionKind::Block(b) => self.block(b, live),
+            ExpressionKind::Reference(_, e)
+            | ExpressionKind::Dereference(e)
+            | ExpressionKind::Unary(_, e)
+            | ExpressionKind::Field(e, _, _)
+            | ExpressionKind::Box(e) => self.expression(e, live),
+            ExpressionKind::Binary(_, l, r) => {
+                let live = self.expression(r, live);
+                self.expression(l, live)
+            },
+            ExpressionKind::Assign(op, target, value) => {
+                // 右辺を評価した後に左辺へ書き込む
+                let live = match &target.kind {
+                    ExpressionKind::Variable(v) => {
+                        self.live_after.insert(target.id, live.clone());
+                        if op == "=" {
+                            live.remove(&v.binding);
+                        } else {
+                            live.insert(v.binding);
+                        }
+                        live
+                    },
+                    _ => self.expression(target, live),
+                };
+                self.expression(value, live)
+            },
+            ExpressionKind::If(condition, then, otherwise) => {
+                // どちらかの分岐で読み出される変数は条件式の後で生存している
+                let mut branches = match otherwise {
+                    Some(a) => self.expression(a, live.clone()),
+                    None => live.clone(),
+                };
+                branches.extend(self.block(then, live));
+                self.expression(condition, branches)
+            },
+            ExpressionKind::While(condition, body) => self.iterate(expression.id, Some(condition), body, live),
+            ExpressionKind::Loop(body) => self.iterate(expression.id, None, body, live),
+            ExpressionKind::Break(value) => {
+                // `break` の後はループの直後へ移動する
+                let after = self.loops.last().map(|a| a.0.clone()).unwrap_or_default();
+                match value {
+                    Some(a) => self.expression(a, after),
+                    None => after,
+                }
+            },
+            ExpressionKind::Continue => self.loops.last().map(|a| a.1.clone()).unwrap_or_default(),
+            ExpressionKind::Call(_, arguments) => arguments.iter().rev().fold(live, |live, a| self.expression(a, live)),
+            ExpressionKind::Struct(_, fields) => fields.iter().rev().fold(live, |live, a| self.expression(&a.value, live)),
+            ExpressionKind::Tuple(elements) | ExpressionKind::Array(elements) | ExpressionKind::Vec(elements) => {
+                elements.iter().rev().fold(live, |live, a| self.expression(a, live))
+            },
+            ExpressionKind::MethodCall(receiver, _, _, arguments) => {
+                let live = arguments.iter().rev().fold(live, |live, a| self.expression(a, live));
+                self.expression(receiver, live)
+            },
+            ExpressionKind::Index(e, index) => {
+                let live = self.expression(index, live);
+                self.expression(e, live)
+            },
+            // `return` の後は関数内のどの変数も使用されない
+            ExpressionKind::Return(value) => match value {
+                Some(a) => self.expression(a, HashSet::new()),
+                None => HashSet::new(),
+            },
+        }
+    }
+
+    /// ループの直前で生存している変数を求めます。
+    /// 繰り返しの先頭で生存している変数が変化しなくなるまで本体の解析を繰り返します。
+    /// - `id` - ループを表す式の番号
+    /// - `condition` - `while` の条件式 (`loop` であれば `None`)
+    /// - `body` - ループの本体
+    /// - `live` - ループの直後で生存している変数
+    fn iterate(&mut self, id: usize, condition: Option<&Expression>, body: &Block, live: HashSet<usize>) -> HashSet<usize> {
+        let mut head: HashSet<usize> = HashSet::new();
+        loop {
+            self.loops.push((live.clone(), head.clone()));
+            let mut next = self.block(body, head.clone());
+            self.loops.pop();
+            if let Some(a) = condition {
+                next.extend(live.iter().copied());
+                next = self.expression(a, next);
+            }
+            next.extend(head.iter().copied());
+            if next == head {
+                break;
+            }
+            head = next;
+        }
+        let mut across = head.clone();
+        across.extend(live);
+        self.live_across.insert(id, across);
+        head
+    }
+}
@@ -1,10 +1,10 @@
 use std::cmp::Ordering;
-use std::{collections::VecDeque, error::Error};
 
-use crate::parser::errors::OperationError;
+use crate::parser::errors::{OperationError, ParseError};
 use crate::{ret_err, parser::errors::InvalidExpressionError};
 
-use super::{ExprParser, ElementType, VarType};
+use super::{ExprParser, VarType};
+use super::token::Span;
 use super::VarType::{Integer, Void, Uninitialized};
 
 impl ExprParser {
@@ -27,223 +27,199 @@ impl ExprParser {
     /// - `op` - 判定する演算子
     pub fn is_monomial(op: &str) -> bool {
         const OPERATORS: [&str; 7] = ["+", "-", "&", "&&", "*", "!", "~"];
-        return OPERATORS.contains(&op);
+        OPERATORS.contains(&op)
     }
 
-    pub fn try_calculate_all(&mut self, mut data: (String, VecDeque<ElementType>)) -> Result<VarType, Box<dyn Error>> {
-        if data.0 != "=" {
-            let mut num = data.1.pop_front().unwrap();
-            while !data.1.is_empty() {
-                let d = data.1.pop_front().unwrap();
-                num = ElementType::Immediate(self.calculate_binomial(&data.0, num, d)?);
-            }
-            return num.to_vartype(self);
-        } else {
-            let mut num = data.1.pop_back().unwrap();
-            while !data.1.is_empty() {
-                let d = data.1.pop_back().unwrap();
-                num = ElementType::Immediate(self.calculate_binomial(&data.0, d, num)?);
-                if data.0 == "&&" {
-                    if matches!(num,  ElementType::Immediate(Integer(0))) {
-                        break;
-                    }
-                } else if data.0 == "||" {
-                    if !matches!(num,  ElementType::Immediate(Integer(0))) {
-                        break;
-                    }
-                }
-            }
-            return num.to_vartype(self);
+    /// 単項演算子の演算を行います。
+    /// - `op` - 演算子
+    /// - `value` - 被演算子の値
+    /// - `span` - 演算の範囲
+    pub fn calculate_monomial(op: &str, value: VarType, span: Span) -> Result<VarType, ParseError> {
+        match (value, op) {
+            (Void | Uninitialized, _) => ret_err!(OperationError::new(span)),
+            (Integer(i), "+") => Ok(Integer(i)),
+            (Integer(i), "-") => Ok(Integer(-i)),
+            (Integer(i), "~") => Ok(Integer(!i)),
+            (Integer(i), "!") => Ok(Integer(if i == 0 {1} else {0})),
+            (Integer(_), a) => ret_err!(InvalidExpressionError::new(format!("Monomial \"{}\" is not for integer.", a))),
+            (VarType::String(_), _) => ret_err!(InvalidExpressionError::from("There are no monomial for string.")),
+            (VarType::Pointer(_) | VarType::Box(_), a) => ret_err!(InvalidExpressionError::new(format!("Monomial \"{}\" is not for pointer.", a))),
+            (VarType::Struct(_, _), a) => ret_err!(InvalidExpressionError::new(format!("Monomial \"{}\" is not for struct.", a))),
+            (VarType::Tuple(_), a) => ret_err!(InvalidExpressionError::new(format!("Monomial \"{}\" is not for tuple.", a))),
+            (VarType::Array(_) | VarType::Vec(_), a) => ret_err!(InvalidExpressionError::new(format!("Monomial \"{}\" is not for array.", a))),
         }
     }
 
-    fn calculate_binomial(&mut self, op: &str, left: ElementType, right: ElementType) -> Result<VarType, Box<dyn Error>> {
+    /// 二項演算子の演算を行います。
+    /// - `op` - 演算子
+    /// - `left` - 左辺の値
+    /// - `right` - 右辺の値
+    /// - `span` - 演算の範囲
+    pub fn calculate_binomial(op: &str, left: VarType, right: VarType, span: Span) -> Result<VarType, ParseError> {
         match op {
-            "+" => left.operation(self, right, |a, b| {
-                match (a, b) {
-                    (Integer(p), Integer(q)) => Ok(Integer(p + q)),
-                    (VarType::String(p), VarType::String(q)) => Ok(VarType::String(format!("{}{}", p, q))),
-                    (Void | Uninitialized, _) | (_, Void | Uninitialized) => ret_err!(OperationError),
-                    _ => ret_err!(InvalidExpressionError::from("Invalid operation.")),
-                }
-            }),
-            "-" => left.operation(self, right, |a, b| {
-                match (a, b) {
-                    (Integer(p), Integer(q)) => Ok(Integer(p - q)),
-                    (Void | Uninitialized, _) | (_, Void | Uninitialized) => ret_err!(OperationError),
-                    _ => ret_err!(InvalidExpressionError::from("Invalid operation.")),
-                }
-            }),
-            "*" => left.operation(self, right, |a, b| {
-                match (a, b) {
-                    (Integer(p), Integer(q)) => Ok(Integer(p * q)),
-                    (Void | Uninitialized, _) | (_, Void | Uninitialized) => ret_err!(OperationError),
-                    _ => ret_err!(InvalidExpressionError::from("Invalid operation.")),
-                }
-            }),
-            "/" => left.operation(self, right, |a, b| {
-                match (a, b) {
-                    (Integer(a), Integer(b)) => Ok(Integer(a / b)),
-                    (Void | Uninitialized, _) | (_, Void | Uninitialized) => ret_err!(OperationError),
-                    _ => ret_err!(InvalidExpressionError::from("Invalid operation.")),
-                }
-            }),
-            "%" => left.operation(self, right, |a, b| {
-                match (a, b) {
-                    (Integer(a), Integer(b)) => Ok(Integer(a % b)),
-                    (Void | Uninitialized, _) | (_, Void | Uninitialized) => ret_err!(OperationError),
-                    _ => ret_err!(InvalidExpressionError::from("Invalid operation.")),
-                }
-            }),
-            "|" => left.operation(self, right, |a, b| {
-                match (a, b) {
-                    (Integer(a), Integer(b)) => Ok(Integer(a | b)),
-                    (Void | Uninitialized, _) | (_, Void | Uninitialized) => ret_err!(OperationError),
-                    _ => ret_err!(InvalidExpressionError::from("Invalid operation.")),
-                }
-            }),
-            "&" => left.operation(self, right, |a, b| {
-                match (a, b) {
-                    (Integer(a), Integer(b)) => Ok(Integer(a & b)),
-                    (Void | Uninitialized, _) | (_, Void | Uninitialized) => ret_err!(OperationError),
-                    _ => ret_err!(InvalidExpressionError::from("Invalid operation.")),
-                }
-            }),
-            "^" => left.operation(self, right, |a, b| {
-                match (a, b) {
-                    (Integer(a), Integer(b)) => Ok(Integer(a ^ b)),
-                    (Void | Uninitialized, _) | (_, Void | Uninitialized) => ret_err!(OperationError),
-                    _ => ret_err!(InvalidExpressionError::from("Invalid operation.")),
-                }
-            }),
-            ">>" => left.operation(self, right, |a, b| {
-                match (a, b) {
-                    (Integer(a), Integer(b)) => Ok(Integer(a >> b)),
-                    (Void | Uninitialized, _) | (_, Void | Uninitialized) => ret_err!(OperationError),
-                    _ => ret_err!(InvalidExpressionError::from("Invalid operation.")),
-                }
-            }),
-            "<<" => left.operation(self, right, |a, b| {
-                match (a, b) {
-                    (Integer(a), Integer(b)) => Ok(Integer(a << b)),
-                    (Void | Uninitialized, _) | (_, Void | Uninitialized) => ret_err!(OperationError),
-                    _ => ret_err!(InvalidExpressionError::from("Invalid operation.")),
-                }
-            }),
-            "==" => left.operation(self, right, |a, b| Ok(Integer(if a == b {1} else {0}))),
-            "!=" => left.operation(self, right, |a, b| Ok(Integer(if a != b {1} else {0}))),
-            ">" => left.operation(self, right, |a, b| {
-                match a.partial_cmp(&b) {
-                    Some(a) => Ok(Integer(if a == Ordering::Greater {1} else {0})),
-                    None => ret_err!(InvalidExpressionError::new(format!("Cannot compare {} and {}.", a, b))),
-                }
-            }),
-            "<" => left.operation(self, right, |a, b| {
-                match a.partial_cmp(&b) {
-                    Some(a) => Ok(Integer(if a == Ordering::Less {1} else {0})),
-                    None => ret_err!(InvalidExpressionError::new(format!("Cannot compare {} and {}.", a, b))),
-                }
-            }),
-            "=>" => left.operation(self, right, |a, b| {
-                match a.partial_cmp(&b) {
-                    Some(a) => Ok(Integer(if a == Ordering::Less {0} else {1})),
-                    None => ret_err!(InvalidExpressionError::new(format!("Cannot compare {} and {}.", a, b))),
-                }
-            }),
-            "=<" => left.operation(self, right, |a, b| {
-                match a.partial_cmp(&b) {
-                    Some(a) => Ok(Integer(if a == Ordering::Greater {0} else {1})),
-                    None => ret_err!(InvalidExpressionError::new(format!("Cannot compare {} and {}.", a, b))),
-                }
-            }),
-            "&&" => left.operation(self, right, |a, b| {
-                match (a, b) {
-                    (Integer(a), Integer(b)) => Ok(Integer(if a != 0 && b != 0 {1} else {0})),
-                    (Void | Uninitialized, _) | (_, Void | Uninitialized) => ret_err!(OperationError),
-                    _ => ret_err!(InvalidExpressionError::from("Invalid operation.")),
-                }
-            }),
-            "||" => left.operation(self, right, |a, b| {
-                match (a, b) {
-                    (Integer(a), Integer(b)) => Ok(Integer(if a != 0 || b != 0 {1} else {0})),
-                    (Void | Uninitialized, _) | (_, Void | Uninitialized) => ret_err!(OperationError),
-                    _ => ret_err!(InvalidExpressionError::from("Invalid operation.")),
-                }
-            }),
-            "=" => left.operation_mut(self, right, |a, b| {*a = b; Ok(Void)}),
-            "+=" => left.operation_mut(self, right, |a, b| {
-                match (a, b) {
+            "+" => match (left, right) {
+                (Integer(p), Integer(q)) => Ok(Integer(p + q)),
+                (VarType::String(p), VarType::String(q)) => Ok(VarType::String(format!("{}{}", p, q))),
+                (Void | Uninitialized, _) | (_, Void | Uninitialized) => ret_err!(OperationError::new(span)),
+                _ => ret_err!(InvalidExpressionError::from("Invalid operation.")),
+            },
+            "-" => match (left, right) {
+                (Integer(p), Integer(q)) => Ok(Integer(p - q)),
+                (Void | Uninitialized, _) | (_, Void | Uninitialized) => ret_err!(OperationError::new(span)),
+                _ => ret_err!(InvalidExpressionError::from("Invalid operation.")),
+            },
+            "*" => match (left, right) {
+                (Integer(p), Integer(q)) => Ok(Integer(p * q)),
+                (Void | Uninitialized, _) | (_, Void | Uninitialized) => ret_err!(OperationError::new(span)),
+                _ => ret_err!(InvalidExpressionError::from("Invalid operation.")),
+            },
+            "/" => match (left, right) {
+                (Integer(a), Integer(b)) => Ok(Integer(a / b)),
+                (Void | Uninitialized, _) | (_, Void | Uninitialized) => ret_err!(OperationError::new(span)),
+                _ => ret_err!(InvalidExpressionError::from("Invalid operation.")),
+            },
+            "%" => match (left, right) {
+                (Integer(a), Integer(b)) => Ok(Integer(a % b)),
+                (Void | Uninitialized, _) | (_, Void | Uninitialized) => ret_err!(OperationError::new(span)),
+                _ => ret_err!(InvalidExpressionError::from("Invalid operation.")),
+            },
+            "|" => match (left, right) {
+                (Integer(a), Integer(b)) => Ok(Integer(a | b)),
+                (Void | Uninitialized, _) | (_, Void | Uninitialized) => ret_err!(OperationError::new(span)),
+                _ => ret_err!(InvalidExpressionError::from("Invalid operation.")),
+            },
+            "&" => match (left, right) {
+                (Integer(a), Integer(b)) => Ok(Integer(a & b)),
+                (Void | Uninitialized, _) | (_, Void | Uninitialized) => ret_err!(OperationError::new(span)),
+                _ => ret_err!(InvalidExpressionError::from("Invalid operation.")),
+            },
+            "^" => match (left, right) {
+                (Integer(a), Integer(b)) => Ok(Integer(a ^ b)),
+                (Void | Uninitialized, _) | (_, Void | Uninitialized) => ret_err!(OperationError::new(span)),
+                _ => ret_err!(InvalidExpressionError::from("Invalid operation.")),
+            },
+            ">>" => match (left, right) {
+                (Integer(a), Integer(b)) => Ok(Integer(a >> b)),
+                (Void | Uninitialized, _) | (_, Void | Uninitialized) => ret_err!(OperationError::new(span)),
+                _ => ret_err!(InvalidExpressionError::from("Invalid operation.")),
+            },
+            "<<" => match (left, right) {
+                (Integer(a), Integer(b)) => Ok(Integer(a << b)),
+                (Void | Uninitialized, _) | (_, Void | Uninitialized) => ret_err!(OperationError::new(span)),
+                _ => ret_err!(InvalidExpressionError::from("Invalid operation.")),
+            },
+            "==" => Ok(Integer(if left == right {1} else {0})),
+            "!=" => Ok(Integer(if left != right {1} else {0})),
+            ">" => match left.partial_cmp(&right) {
+                Some(a) => Ok(Integer(if a == Ordering::Greater {1} else {0})),
+                None => ret_err!(InvalidExpressionError::new(format!("Cannot compare {} and {}.", left, right))),
+            },
+            "<" => match left.partial_cmp(&right) {
+                Some(a) => Ok(Integer(if a == Ordering::Less {1} else {0})),
+                None => ret_err!(InvalidExpressionError::new(format!("Cannot compare {} and {}.", left, right))),
+            },
+            ">=" => match left.partial_cmp(&right) {
+                Some(a) => Ok(Integer(if a == Ordering::Less {0} else {1})),
+                None => ret_err!(InvalidExpressionError::new(format!("Cannot compare {} and {}.", left, right))),
+            },
+            "<=" => match left.partial_cmp(&right) {
+                Some(a) => Ok(Integer(if a == Ordering::Greater {0} else {1})),
+                None => ret_err!(InvalidExpressionError::new(format!("Cannot compare {} and {}.", left, right))),
+            },
+            "&&" => match (left, right) {
+                (Integer(a), Integer(b)) => Ok(Integer(if a != 0 && b != 0 {1} else {0})),
+                (Void | Uninitialized, _) | (_, Void | Uninitialized) => ret_err!(OperationError::new(span)),
+                _ => ret_err!(InvalidExpressionError::from("Invalid operation.")),
+            },
+            "||" => match (left, right) {
+                (Integer(a), Integer(b)) => Ok(Integer(if a != 0 || b != 0 {1} else {0})),
+                (Void | Uninitialized, _) | (_, Void | Uninitialized) => ret_err!(OperationError::new(span)),
+                _ => ret_err!(InvalidExpressionError::from("Invalid operation.")),
+            },
+            a => ret_err!(InvalidExpressionError::new(format!("Invalid operator \"{}\".", a))),
+        }
+    }
+
+    /// 複合代入演算子の演算を行います。
+    /// - `op` - 演算子
+    /// - `target` - 代入先の値
+    /// - `right` - 右辺の値
+    /// - `span` - 演算の範囲
+    pub fn calculate_assign(op: &str, target: &mut VarType, right: VarType, span: Span) -> Result<VarType, ParseError> {
+        match op {
+            "+=" => {
+                match (target, right) {
                     (Integer(a), Integer(b)) => *a += b,
                     (VarType::String(a), VarType::String(b)) => *a = format!("{}{}", a, b),
-                    (Void | Uninitialized, _) | (_, Void | Uninitialized) => ret_err!(OperationError),
+                    (Void | Uninitialized, _) | (_, Void | Uninitialized) => ret_err!(OperationError::new(span)),
                     _ => ret_err!(InvalidExpressionError::from("Invalid operation.")),
                 }
-                return Ok(Void);
-            }),
-            "-=" => left.operation_mut(self, right, |a, b| {
-                match (a, b) {
+                Ok(Void)
+            },
+            "-=" => {
+                match (target, right) {
                     (Integer(a), Integer(b)) => *a -= b,
-                    (Void | Uninitialized, _) | (_, Void | Uninitialized) => ret_err!(OperationError),
+                    (Void | Uninitialized, _) | (_, Void | Uninitialized) => ret_err!(OperationError::new(span)),
                     _ => ret_err!(InvalidExpressionError::from("Invalid operation.")),
                 }
-                return Ok(Void);
-            }),
-            "*=" => left.operation_mut(self, right, |a, b| {
-                match (a, b) {
+                Ok(Void)
+            },
+            "*=" => {
+                match (target, right) {
                     (Integer(a), Integer(b)) => {*a *= b; Ok(Void)},
-                    (Void | Uninitialized, _) | (_, Void | Uninitialized) => ret_err!(OperationError),
+                    (Void | Uninitialized, _) | (_, Void | Uninitialized) => ret_err!(OperationError::new(span)),
                     _ => ret_err!(InvalidExpressionError::from("Invalid operation.")),
                 }
-            }),
-            "/=" => left.operation_mut(self, right, |a, b| {
-                match (a, b) {
+            },
+            "/=" => {
+                match (target, right) {
                     (Integer(a), Integer(b)) => {*a /= b; Ok(Void)},
-                    (Void | Uninitialized, _) | (_, Void | Uninitialized) => ret_err!(OperationError),
+                    (Void | Uninitialized, _) | (_, Void | Uninitialized) => ret_err!(OperationError::new(span)),
                     _ => ret_err!(InvalidExpressionError::from("Invalid operation.")),
                 }
-            }),
-            "%=" => left.operation_mut(self, right, |a, b| {
-                match (a, b) {
+            },
+            "%=" => {
+                match (target, right) {
                     (Integer(a), Integer(b)) => {*a %= b; Ok(Void)},
-                    (Void | Uninitialized, _) | (_, Void | Uninitialized) => ret_err!(OperationError),
+                    (Void | Uninitialized, _) | (_, Void | Uninitialized) => ret_err!(OperationError::new(span)),
                     _ => ret_err!(InvalidExpressionError::from("Invalid operation.")),
                 }
-            }),
-            "|=" => left.operation_mut(self, right, |a, b| {
-                match (a, b) {
+            },
+            "|=" => {
+                match (target, right) {
                     (Integer(a), Integer(b)) => {*a |= b; Ok(Void)},
-                    (Void | Uninitialized, _) | (_, Void | Uninitialized) => ret_err!(OperationError),
+                    (Void | Uninitialized, _) | (_, Void | Uninitialized) => ret_err!(OperationError::new(span)),
                     _ => ret_err!(InvalidExpressionError::from("Invalid operation.")),
                 }
-            }),
-            "&=" => left.operation_mut(self, right, |a, b| {
-                match (a, b) {
+            },
+            "&=" => {
+                match (target, right) {
                     (Integer(a), Integer(b)) => {*a &= b; Ok(Void)},
-                    (Void | Uninitialized, _) | (_, Void | Uninitialized) => ret_err!(OperationError),
+                    (Void | Uninitialized, _) | (_, Void | Uninitialized) => ret_err!(OperationError::new(span)),
                     _ => ret_err!(InvalidExpressionError::from("Invalid operation.")),
                 }
-            }),
-            "^=" => left.operation_mut(self, right, |a, b| {
-                match (a, b) {
+            },
+            "^=" => {
+                match (target, right) {
                     (Integer(a), Integer(b)) => {*a ^= b; Ok(Void)},
-                    (Void | Uninitialized, _) | (_, Void | Uninitialized) => ret_err!(OperationError),
+                    (Void | Uninitialized, _) | (_, Void | Uninitialized) => ret_err!(OperationError::new(span)),
                     _ => ret_err!(InvalidExpressionError::from("Invalid operation.")),
                 }
-            }),
-            ">>=" => left.operation_mut(self, right, |a, b| {
-                match (a, b) {
+            },
+            ">>=" => {
+                match (target, right) {
                     (Integer(a), Integer(b)) => {*a >>= b; Ok(Void)},
-                    (Void | Uninitialized, _) | (_, Void | Uninitialized) => ret_err!(OperationError),
+                    (Void | Uninitialized, _) | (_, Void | Uninitialized) => ret_err!(OperationError::new(span)),
                     _ => ret_err!(InvalidExpressionError::from("Invalid operation.")),
                 }
-            }),
-            "<<=" => left.operation_mut(self, right, |a, b| {
-                match (a, b) {
+            },
+            "<<=" => {
+                match (target, right) {
                     (Integer(a), Integer(b)) => {*a <<= b; Ok(Void)},
-                    (Void | Uninitialized, _) | (_, Void | Uninitialized) => ret_err!(OperationError),
+                    (Void | Uninitialized, _) | (_, Void | Uninitialized) => ret_err!(OperationError::new(span)),
                     _ => ret_err!(InvalidExpressionError::from("Invalid operation.")),
                 }
-            }),
+            },
             a => ret_err!(InvalidExpressionError::new(format!("Invalid operator \"{}\".", a))),
         }
     }
@@ -1,32 +1,36 @@
 use super::ExprParser;
+use super::token::{Span, Token};
 
 impl ExprParser {
     /// 入力された文字列を要素毎に分割します。
     /// * `cmd` - 分割する文字列
-    pub fn split_elements(&mut self, cmd: &String) {
-        let mut tmp = cmd.chars().rev().collect::<Vec<char>>();
+    pub fn split_elements(&mut self, cmd: &str) {
+        let mut tmp = cmd.char_indices().rev().collect::<Vec<(usize, char)>>();
         let mut word: Vec<char> = Vec::new();
+        let mut word_start = 0;
+        let mut word_end = 0;
         let mut is_string = false;
         let mut comment_out: Option<CommentType> = None;
-        while !tmp.is_empty() {
-            let a = tmp.pop().unwrap();
+        let lines = LineIndex::new(cmd);
+        while let Some((i, a)) = tmp.pop() {
             if let Some(c) = comment_out {
                 if c == CommentType::SingleLine && a == '\n' {
                     comment_out = None;
-                } else if a == '*' && tmp.last() == Some(&'/') {
+                } else if c == CommentType::MultiLine && a == '*' && tmp.last().map(|a| a.1) == Some('/') {
                     tmp.pop();
                     comment_out = None;
                 }
                 continue;
             }
             
-            if a == '/' {
-                match tmp.last() {
-                    Some(&'/') => {
+            if a == '/' && !is_string {
+                match tmp.last().map(|a| a.1) {
+                    Some('/') => {
                         comment_out = Some(CommentType::SingleLine);
                         continue;
                     }
-                    Some(&'*') => {
+                    Some('*') => {
+                        tmp.pop();
                         comment_out = Some(CommentType::MultiLine);
                         continue;
                     },
@@ -38,41 +42,76 @@ impl ExprParser {
                 if a == '"' && word.last().map(|a| a != &'\\').unwrap_or(true) {
                     is_string = !is_string;
                 }
-                word.push(a);
             } else {
                 match CharType::get_chartype(a) {
                     CharType::Normal => {
-                        if !word.is_empty() && CharType::get_chartype(*word.last().unwrap()) != CharType::Normal {
-                            self.cmds.push(String::from_iter(&word));
-                            word.clear();
+                        // `'a` のような寿命は一つの要素として扱う
+                        if !word.is_empty() && word != ['\''] && CharType::get_chartype(*word.last().unwrap()) != CharType::Normal {
+                            self.push_element(&mut word, lines.span(word_start, word_end));
                         }
-                        word.push(a);
                     },
                     CharType::Punctuation => {
                         if a == '"' {
                             is_string = !is_string;
-                        } else {
-                            if !word.is_empty() && (Self::get_priority(String::from_iter([word.clone(), vec![a]].concat()).as_str()).is_none()) {
-                                self.cmds.push(String::from_iter(&word));
-                                word.clear();
-                            }
+                            self.push_element(&mut word, lines.span(word_start, word_end));
+                        } else if !word.is_empty() && !Self::is_compound_punctuation(String::from_iter([word.clone(), vec![a]].concat()).as_str()) {
+                            self.push_element(&mut word, lines.span(word_start, word_end));
                         }
-                        word.push(a);
                     },
                     CharType::WhiteSpace => {
-                        if !word.is_empty() {
-                            self.cmds.push(String::from_iter(&word));
-                            word.clear();
-                        }
+                        self.push_element(&mut word, lines.span(word_start, word_end));
+                        continue;
                     },
                 }
             }
+            if word.is_empty() {
+                word_start = i;
+            }
+            word.push(a);
+            word_end = i + a.len_utf8();
         }
+        self.push_element(&mut word, lines.span(word_start, word_end));
+    }
+
+    /// 複数の記号からなる一つの要素であるかを取得します。
+    /// * `text` - 判定する文字列
+    fn is_compound_punctuation(text: &str) -> bool {
+        Self::get_priority(text).is_some() || text == "->" || text == "::"
+    }
+
+    /// 分割中の単語を要素として追加します。単語が空の場合は何もしません。
+    /// * `word` - 分割中の単語
+    /// * `span` - 単語のソースコード上の範囲
+    fn push_element(&mut self, word: &mut Vec<char>, span: Span) {
         if !word.is_empty() {
-            self.cmds.push(String::from_iter(word));
+            self.cmds.push(Token::new(String::from_iter(word.iter()), span));
+            word.clear();
         }
     }
+}
+
+/// 入力された文字列の各行の開始位置を保持し、位置から行番号と列番号を求める構造体です。
+struct LineIndex<'a> {
+    source: &'a str,
+    starts: Vec<usize>,
+}
 
+impl<'a> LineIndex<'a> {
+    fn new(source: &'a str) -> LineIndex<'a> {
+        let starts = std::iter::once(0)
+            .chain(source.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+        LineIndex { source, starts }
+    }
+
+    /// 位置の範囲から `Span` を作成します。
+    /// * `start` - 範囲の開始位置
+    /// * `end` - 範囲の終了位置
+    fn span(&self, start: usize, end: usize) -> Span {
+        let line = self.starts.partition_point(|a| *a <= start);
+        let column = self.source[self.starts[line - 1]..start].chars().count() + 1;
+        Span { start, end, line, column }
+    }
 }
 
 /// 文字の種類を定義します。要素に分割する時に使用します。
@@ -0,0 +1,929 @@
+use std::collections::{HashMap, HashSet};
+
+use log::{info, trace};
+
+use crate::ret_err;
+
+use super::ExprParser;
+use super::ast::{
+    Block, BUILTIN_DROP, Declaration, Expression, ExpressionKind, FieldDefinition, FieldValue, Function, Lifetime, Program, Statement, Struct, Type, TypeKind,
+    Variable,
+};
+use super::borrow::BorrowKind;
+use super::errors::{InvalidExpressionError, ParseError, VariableNotFoundError};
+use super::token::{Span, Token, TokenKind};
+
+/// 代入演算子の優先順位です。代入演算子のみ右結合として扱います。
+const ASSIGN_PRIORITY: usize = 6;
+
+/// 分割された要素から構文木を作成するパーサです。
+/// 変数名は解釈時に宣言と対応付けられます。
+/// 文の解釈に失敗した場合はエラーを記録し、次の `;` または `}` から解釈を再開します。
+pub struct SyntaxParser<'a> {
+    cmds: &'a [Token],
+    pointer: usize,
+    scopes: Vec<HashMap<String, usize>>,
+    bindings: usize,
+    nodes: usize,
+    /// 解釈中のループの深さ
+    loops: usize,
+    /// 関数の本体を解釈中であるか
+    in_function: bool,
+    /// 定義された関数
+    functions: HashMap<String, Function>,
+    /// 定義された構造体
+    structs: HashMap<String, Struct>,
+    /// 入力全体で定義されている構造体名 (構造体は使用より後で定義されることがあるため、解釈の前に集めます)
+    struct_names: HashSet<String>,
+    /// 関数呼び出しの関数名、引数の数、範囲 (関数は呼び出しより後で定義されることがあるため、最後に確認します)
+    calls: Vec<(String, usize, Span)>,
+    /// 型注釈の `>>` のうち、一つ目の `>` のみを閉じかっことして読んだか
+    half_closed: bool,
+    errors: Vec<ParseError>,
+}
+
+impl<'a> SyntaxParser<'a> {
+    pub fn new(cmds: &'a [Token]) -> SyntaxParser<'a> {
+        let struct_names = cmds
+            .windows(2)
+            .filter(|a| a[0].text == "struct" && a[1].kind == TokenKind::Identifier)
+            .map(|a| a[1].text.clone())
+            .collect();
+        SyntaxParser {
+            cmds,
+            pointer: 0,
+            scopes: vec![HashMap::new()],
+            bindings: 0,
+            nodes: 0,
+            loops: 0,
+            in_function: false,
+            functions: HashMap::new(),
+            structs: HashMap::new(),
+            struct_names,
+            calls: Vec::new(),
+            half_closed: false,
+            errors: Vec::new(),
+        }
+    }
+
+    /// 要素全体をひとつのプログラムとして解釈します。
+    /// 解釈できなかった文があれば、見つかったすべてのエラーを返します。
+    pub fn parse_program(&mut self) -> Result<Program, Vec<ParseError>> {
+        info!("Start building syntax tree...");
+        let main = self.parse_sentence(self.span_at(0));
+        if let Some(a) = self.cmds.get(self.pointer) {
+            let error = InvalidExpressionError::new(format!("Unexpected \"{}\".", a.text)).with_span(a.span);
+            self.errors.push(error.into());
+        }
+        for (name, count, span) in std::mem::take(&mut self.calls) {
+            let expected = match self.functions.get(&name) {
+                Some(a) => a.parameters.len(),
+                // 同じ名前の関数が定義されていなければ、組み込みの関数 `drop` とする
+                None if name == BUILTIN_DROP => 1,
+                None => {
+                    self.errors.push(VariableNotFoundError::function(name, span).into());
+                    continue;
+                },
+            };
+            if expected != count {
+                let error = InvalidExpressionError::new(format!(
+                    "Function \"{}\" takes {} argument(s) but {} argument(s) were supplied.",
+                    name,
+                    expected,
+                    count
+                ));
+                self.errors.push(error.with_span(span).into());
+            }
+        }
+        if self.errors.is_empty() {
+            Ok(Program { main, functions: std::mem::take(&mut self.functions), structs: std::mem::take(&mut self.structs) })
+        } else {
+            Err(std::mem::take(&mut self.errors))
+        }
+    }
+
+    /// 次に解釈する要素を取得します。
+    fn peek(&self) -> Option<&'a str> {
+        self.cmds.get(self.pointer).map(|a| a.text.as_str())
+    }
+
+    /// 指定した位置の要素の範囲を取得します。末尾を超える場合は入力の終わりを指す空の範囲を返します。
+    /// - `index` - 要素の位置
+    fn span_at(&self, index: usize) -> Span {
+        match self.cmds.get(index) {
+            Some(a) => a.span,
+            None => self.cmds.last()
+                .map(|a| Span { start: a.span.end, column: a.span.column + a.text.chars().count(), ..a.span })
+                .unwrap_or_default(),
+        }
+    }
+
+    /// 新しく式を作成します。
+    /// - `span` - 式の範囲
+    /// - `kind` - 式の種類
+    fn new_expression(&mut self, span: Span, kind: ExpressionKind) -> Expression {
+        self.nodes += 1;
+        Expression { id: self.nodes, span, kind }
+    }
+
+    /// 現在のスコープに変数を宣言します。
+    /// 同じ名前の変数が宣言済みであっても新しい変数を作成し、以降はその名前で新しい変数を参照します (シャドーイング)。
+    /// - `name` - 変数名
+    fn declare(&mut self, name: &str) -> usize {
+        self.bindings += 1;
+        self.scopes.last_mut().unwrap().insert(name.to_string(), self.bindings);
+        self.bindings
+    }
+
+    /// 閉じかっこまたは末尾までの文の並びをブロックとして解釈する関数です。
+    /// 解釈できなかった文はエラーを記録して読み飛ばします。
+    /// * `start` - ブロックの開始位置の範囲
+    fn parse_sentence(&mut self, start: Span) -> Block {
+        info!("Start parsing as sentence from {}.", self.pointer);
+        let mut statements = Vec::new();
+        let mut declarations = Vec::new();
+        let mut tail = None;
+        while let Some(a) = self.peek() {
+            trace!("Pointer: {} ({})", self.pointer, a);
+            let begin = self.pointer;
+            match a {
+                "}" => break,
+                ";" => self.pointer += 1,
+                _ => match self.parse_statement(&mut declarations) {
+                    Ok(Some(Statement::Expression(a))) if matches!(self.peek(), Some("}") | None) => {
+                        tail = Some(Box::new(a));
+                        break;
+                    },
+                    Ok(Some(a)) => statements.push(a),
+                    Ok(None) => {},
+                    Err(e) => {
+                        self.errors.push(e);
+                        self.recover(begin);
+                    },
+                },
+            }
+        }
+        let end = self.span_at(self.pointer);
+        Block { statements, tail, declarations, span: start.to(end), end }
+    }
+
+    /// 文をひとつ解釈する関数です。関数や構造体の定義であれば `None` を返します。
+    /// * `declarations` - ブロック内で宣言された変数の一覧
+    fn parse_statement(&mut self, declarations: &mut Vec<usize>) -> Result<Option<Statement>, ParseError> {
+        match self.peek() {
+            Some("fn") => {
+                self.parse_function()?;
+                Ok(None)
+            },
+            Some("struct") => {
+                self.parse_struct(None)?;
+                Ok(None)
+            },
+            Some("#") => {
+                let copy = self.parse_derive()?;
+                if self.peek() != Some("struct") {
+                    ret_err!(InvalidExpressionError::from("\"struct\" was expected.").with_span(self.span_at(self.pointer)))
+                }
+                self.parse_struct(copy)?;
+                Ok(None)
+            },
+            Some("let") => {
+                self.pointer += 1;
+                let mutable = self.peek() == Some("mut");
+                if mutable {
+                    self.pointer += 1;
+                }
+                let (name, span) = match self.cmds.get(self.pointer) {
+                    Some(a) if a.kind == TokenKind::Identifier => (a.text.clone(), a.span),
+                    _ => ret_err!(InvalidExpressionError::from("Next of \"let\" keyword must be variable name.").with_span(self.span_at(self.pointer))),
+                };
+                self.pointer += 1;
+                let ty = if self.peek() == Some(":") {
+                    self.pointer += 1;
+                    Some(self.parse_type()?)
+                } else {
+                    None
+                };
+                // 初期化式は宣言より前に解釈し、宣言する変数自身を参照できないようにする
+                let value = if self.peek() == Some("=") {
+                    self.pointer += 1;
+                    match self.parse_expression() {
+                        Ok(a) => Some(a),
+                        Err(e) => {
+                            // 初期化式が誤っていても変数は宣言し、以降の文で変数が見つからないエラーが続かないようにする
+                            let binding = self.declare(&name);
+                            declarations.push(binding);
+                            return Err(e);
+                        },
+                    }
+                } else {
+                    None
+                };
+                // `;` がなくても変数は宣言し、以降の文で変数が見つからないエラーが続かないようにする
+                if !matches!(self.peek(), Some(";" | "}") | None) {
+                    let error = InvalidExpressionError::from("\";\" was expected.").with_span(self.span_at(self.pointer));
+                    self.errors.push(error.into());
+                }
+                let binding = self.declare(&name);
+                declarations.push(binding);
+                let initializer = value.map(|a| {
+                    let target = self.new_expression(span, ExpressionKind::Variable(Variable { name: name.clone(), binding }));
+                    self.new_expression(span.to(a.span), ExpressionKind::Assign("=".to_string(), Box::new(target), Box::new(a)))
+                });
+                Ok(Some(Statement::Let(Declaration { name, binding, span, ty, mutable }, initializer)))
+            },
+            Some("debug") => {
+                self.pointer += 1;
+                Ok(Some(Statement::Debug(self.parse_expression()?)))
+            },
+            // 文の先頭のブロックと `if` 式などは、後に演算子が続いても一つの文として扱う
+            Some("{" | "if" | "while" | "loop") => {
+                let expression = self.parse_element()?;
+                match self.peek() {
+                    Some(a) if ExprParser::get_priority(a).is_some() && !ExprParser::is_monomial(a) => {
+                        ret_err!(InvalidExpressionError::new(format!("Invalid operator \"{}\".", a)).with_span(self.span_at(self.pointer)))
+                    },
+                    _ => Ok(Some(Statement::Expression(expression))),
+                }
+            },
+            _ => {
+                let expression = self.parse_expression()?;
+                match self.peek() {
+                    Some(";" | "}") | None => Ok(Some(Statement::Expression(expression))),
+                    // ブロックと `if` 式は `;` なしで文として扱う
+                    Some(_) if expression.is_block_like() => Ok(Some(Statement::Expression(expression))),
+                    Some(a) => ret_err!(InvalidExpressionError::new(format!("Invalid operator \"{}\".", a)).with_span(self.span_at(self.pointer))),
+                }
+            },
+        }
+    }
+
+    /// 関数の定義を解釈する関数です。関数の本体からは外側の変数を参照できません。
+    fn parse_function(&mut self) -> Result<(), ParseError> {
+        self.pointer += 1;
+        let (name, span) = match self.cmds.get(self.pointer) {
+            Some(a) if a.kind == TokenKind::Identifier => (a.text.clone(), a.span),
+            _ => ret_err!(InvalidExpressionError::from("Next of \"fn\" keyword must be function name.").with_span(self.span_at(self.pointer))),
+        };
+        self.pointer += 1;
+        let lifetimes = self.parse_lifetime_parameters()?;
+        if self.peek() != Some("(") {
+            ret_err!(InvalidExpressionError::from("\"(\" was expected.").with_span(self.span_at(self.pointer)))
+        }
+        self.pointer += 1;
+        let scopes = std::mem::replace(&mut self.scopes, vec![HashMap::new()]);
+        let loops = std::mem::take(&mut self.loops);
+        let in_function = std::mem::replace(&mut self.in_function, true);
+        let result = self.parse_function_body(name, span, lifetimes);
+        self.scopes = scopes;
+        self.loops = loops;
+        self.in_function = in_function;
+        let function = result?;
+        if self.functions.contains_key(&function.name) {
+            ret_err!(InvalidExpressionError::new(format!("The name \"{}\" is defined multiple times.", function.name)).with_span(span))
+        }
+        self.functions.insert(function.name.clone(), function);
+        Ok(())
+    }
+
+    /// 構造体の定義を解釈する関数です。
+    /// * `copy` - `#[derive(Copy)]` の `Copy` の範囲
+    fn parse_struct(&mut self, copy: Option<Span>) -> Result<(), ParseError> {
+        self.pointer += 1;
+        let (name, span) = match self.cmds.get(self.pointer) {
+            Some(a) if a.kind == TokenKind::Identifier => (a.text.clone(), a.span),
+            _ => ret_err!(InvalidExpressionError::from("Next of \"struct\" keyword must be struct name.").with_span(self.span_at(self.pointer))),
+        };
+        self.pointer += 1;
+        if self.peek() != Some("{") {
+            ret_err!(InvalidExpressionError::from("\"{\" was expected.").with_span(self.span_at(self.pointer)))
+        }
+        self.pointer += 1;
+        let mut fields: Vec<FieldDefinition> = Vec::new();
+        while self.peek() != Some("}") {
+            let field = match self.cmds.get(self.pointer) {
+                Some(a) if a.kind == TokenKind::Identifier => a,
+                _ => ret_err!(InvalidExpressionError::from("Field name was expected.").with_span(self.span_at(self.pointer))),
+            };
+            if fields.iter().any(|a| a.name == field.text) {
+                ret_err!(InvalidExpressionError::new(format!("Field \"{}\" is already declared.", field.text)).with_span(field.span))
+            }
+            self.pointer += 1;
+            if self.peek() != Some(":") {
+                ret_err!(InvalidExpressionError::from("\":\" was expected.").with_span(self.span_at(self.pointer)))
+            }
+            self.pointer += 1;
+            let ty = self.parse_type()?;
+            fields.push(FieldDefinition { name: field.text.clone(), span: field.span.to(ty.span), ty });
+            match self.peek() {
+                Some(",") => self.pointer += 1,
+                Some("}") => {},
+                _ => ret_err!(InvalidExpressionError::from("\",\" or \"}\" was expected.").with_span(self.span_at(self.pointer))),
+            }
+        }
+        self.pointer += 1;
+        if self.structs.contains_key(&name) {
+            ret_err!(InvalidExpressionError::new(format!("The name \"{}\" is defined multiple times.", name)).with_span(span))
+        }
+        self.structs.insert(name.clone(), Struct { name, fields, span, copy });
+        Ok(())
+    }
+
+    /// 構造体の定義の前に書かれた `#[derive(Clone, Copy)]` のような属性を解釈する関数です。
+    /// 指定できるのは `Clone` と `Copy` のみで、`Copy` の範囲を返します。
+    fn parse_derive(&mut self) -> Result<Option<Span>, ParseError> {
+        let mut copy = None;
+        while self.peek() == Some("#") {
+            for expected in ["#", "[", "derive", "("] {
+                if self.peek() != Some(expected) {
+                    ret_err!(InvalidExpressionError::new(format!("\"{}\" was expected.", expected)).with_span(self.span_at(self.pointer)))
+                }
+                self.pointer += 1;
+            }
+            while self.peek() != Some(")") {
+                match self.cmds.get(self.pointer) {
+                    Some(a) if a.text == "Copy" => copy = Some(a.span),
+                    Some(a) if a.text == "Clone" => {},
+                    Some(a) if a.kind == TokenKind::Identifier => {
+                        ret_err!(InvalidExpressionError::new(format!("Trait \"{}\" cannot be derived.", a.text)).with_span(a.span))
+                    },
+                    _ => ret_err!(InvalidExpressionError::from("Trait name was expected.").with_span(self.span_at(self.pointer))),
+                }
+                self.pointer += 1;
+                match self.peek() {
+                    Some(",") => self.pointer += 1,
+                    Some(")") => {},
+                    _ => ret_err!(InvalidExpressionError::from("\",\" or \")\" was expected.").with_span(self.span_at(self.pointer))),
+                }
+            }
+            self.pointer += 1;
+            if self.peek() != Some("]") {
+                ret_err!(InvalidExpressionError::from("\"]\" was expected.").with_span(self.span_at(self.pointer)))
+            }
+            self.pointer += 1;
+        }
+        Ok(copy)
+    }
+
+    /// 構造体式を解釈する関数です。`Point { x }` は `Point { x: x }` として扱います。
+    /// フィールドの過不足は、構造体の定義が分かる型検査で確認します。
+    /// 同じフィールドが複数回指定された場合は、閉じ括弧まで読み進めてからエラーを返します。
+    fn parse_struct_literal(&mut self) -> Result<Expression, ParseError> {
+        let token = &self.cmds[self.pointer];
+        self.pointer += 2;
+        let mut fields: Vec<FieldValue> = Vec::new();
+        let mut duplicate = None;
+        while self.peek() != Some("}") {
+            let field = match self.cmds.get(self.pointer) {
+                Some(a) if a.kind == TokenKind::Identifier => a,
+                _ => ret_err!(InvalidExpressionError::from("Field name was expected.").with_span(self.span_at(self.pointer))),
+            };
+            if duplicate.is_none() && fields.iter().any(|a| a.name == field.text) {
+                duplicate = Some(InvalidExpressionError::new(format!("Field \"{}\" is specified more than once.", field.text)).with_span(field.span));
+            }
+            let value = if self.cmds.get(self.pointer + 1).is_some_and(|a| a.text == ":") {
+                self.pointer += 2;
+                self.parse_expression()?
+            } else {
+                self.parse_element()?
+            };
+            fields.push(FieldValue { name: field.text.clone(), span: field.span, value });
+            match self.peek() {
+                Some(",") => self.pointer += 1,
+                Some("}") => {},
+                _ => ret_err!(InvalidExpressionError::from("\",\" or \"}\" was expected.").with_span(self.span_at(self.pointer))),
+            }
+        }
+        let span = token.span.to(self.span_at(self.pointer));
+        self.pointer += 1;
+        if let Some(error) = duplicate {
+            ret_err!(error)
+        }
+        Ok(self.new_expression(span, ExpressionKind::Struct(token.text.clone(), fields)))
+    }
+
+    /// 関数名に続く寿命引数の宣言 (`<'a, 'b>`) を解釈する関数です。宣言がなければ空の一覧を返します。
+    fn parse_lifetime_parameters(&mut self) -> Result<Vec<Lifetime>, ParseError> {
+        let mut lifetimes: Vec<Lifetime> = Vec::new();
+        if self.peek() != Some("<") {
+            return Ok(lifetimes);
+        }
+        self.pointer += 1;
+        while self.peek() != Some(">") {
+            let lifetime = match self.cmds.get(self.pointer) {
+                Some(a) if a.kind == TokenKind::Lifetime => Lifetime { name: a.text.clone(), span: a.span },
+                _ => ret_err!(InvalidExpressionError::from("Lifetime parameter was expected.").with_span(self.span_at(self.pointer))),
+            };
+            if lifetimes.iter().any(|a| a.name == lifetime.name) {
+                ret_err!(InvalidExpressionError::new(format!("Lifetime \"{}\" is declared more than once.", lifetime.name)).with_span(lifetime.span))
+            }
+            lifetimes.push(lifetime);
+            self.pointer += 1;
+            match self.peek() {
+                Some(",") => self.pointer += 1,
+                Some(">") => {},
+                _ => ret_err!(InvalidExpressionError::from("\",\" or \">\" was expected.").with_span(self.span_at(self.pointer))),
+            }
+        }
+        self.pointer += 1;
+        Ok(lifetimes)
+    }
+
+    /// 型注釈を解釈する関数です。
+    fn parse_type(&mut self) -> Result<Type, ParseError> {
+        let token = match self.cmds.get(self.pointer) {
+            Some(a) => a,
+            None => ret_err!(InvalidExpressionError::from("Type was expected.").with_span(self.span_at(self.pointer))),
+        };
+        self.pointer += 1;
+        match (token.kind, token.text.as_str()) {
+            (_, "&") => {
+                let lifetime = match self.cmds.get(self.pointer) {
+                    Some(a) if a.kind == TokenKind::Lifetime => {
+                        self.pointer += 1;
+                        Some(Lifetime { name: a.text.clone(), span: a.span })
+                    },
+                    _ => None,
+                };
+                let kind = if self.peek() == Some("mut") {
+                    self.pointer += 1;
+                    BorrowKind::Mutable
+                } else {
+                    BorrowKind::Shared
+                };
+                let inner = self.parse_type()?;
+                Ok(Type { span: token.span.to(inner.span), kind: TypeKind::Reference(lifetime, kind, Box::new(inner)) })
+            },
+            (_, "(") => {
+                let mut elements = Vec::new();
+                while self.peek() != Some(")") {
+                    elements.push(self.parse_type()?);
+                    match self.peek() {
+                        Some(",") => self.pointer += 1,
+                        Some(")") => {},
+                        _ => ret_err!(InvalidExpressionError::from("\",\" or \")\" was expected.").with_span(self.span_at(self.pointer))),
+                    }
+                }
+                let span = token.span.to(self.span_at(self.pointer));
+                self.pointer += 1;
+                Ok(Type { span, kind: TypeKind::Tuple(elements) })
+            },
+            (_, "[") => {
+                let element = self.parse_type()?;
+                if self.peek() != Some(";") {
+                    ret_err!(InvalidExpressionError::from("\";\" was expected.").with_span(self.span_at(self.pointer)))
+                }
+                self.pointer += 1;
+                let length = match self.cmds.get(self.pointer).filter(|a| a.kind == TokenKind::Integer).and_then(|a| a.text.parse().ok()) {
+                    Some(a) => a,
+                    None => ret_err!(InvalidExpressionError::from("Array length was expected.").with_span(self.span_at(self.pointer))),
+                };
+                self.pointer += 1;
+                if self.peek() != Some("]") {
+                    ret_err!(InvalidExpressionError::from("\"]\" was expected.").with_span(self.span_at(self.pointer)))
+                }
+                let span = token.span.to(self.span_at(self.pointer));
+                self.pointer += 1;
+                Ok(Type { span, kind: TypeKind::Array(Box::new(element), length) })
+            },
+            (TokenKind::Identifier, a @ ("Vec" | "Box")) if self.peek() == Some("<") => {
+                self.pointer += 1;
+                let inner = Box::new(self.parse_type()?);
+                let end = self.parse_type_close()?;
+                let kind = if a == "Vec" { TypeKind::Vec(inner) } else { TypeKind::Box(inner) };
+                Ok(Type { span: token.span.to(end), kind })
+            },
+            (TokenKind::Identifier, a) => Ok(Type { span: token.span, kind: TypeKind::Named(a.to_string()) }),
+            _ => ret_err!(InvalidExpressionError::from("Type was expected.").with_span(token.span)),
+        }
+    }
+
+    /// 型引数の閉じかっこ `>` を解釈する関数です。`Vec<Vec<i32>>` の `>>` は二つの閉じかっことして扱います。
+    fn parse_type_close(&mut self) -> Result<Span, ParseError> {
+        let span = self.span_at(self.pointer);
+        match self.peek() {
+            Some(">") => {},
+            Some(">>") if !self.half_closed => {
+                self.half_closed = true;
+                return Ok(Span { end: span.start + 1, ..span });
+            },
+            Some(">>") => self.half_closed = false,
+            _ => ret_err!(InvalidExpressionError::from("\">\" was expected.").with_span(span)),
+        }
+        self.pointer += 1;
+        Ok(span)
+    }
+
+    /// 関数の引数と本体を解釈する関数です。
+    /// * `name` - 関数名
+    /// * `span` - 関数名の範囲
+    /// * `lifetimes` - 宣言された寿命引数
+    fn parse_function_body(&mut self, name: String, span: Span, lifetimes: Vec<Lifetime>) -> Result<Function, ParseError> {
+        let mut parameters: Vec<Declaration> = Vec::new();
+        while self.peek() != Some(")") {
+            let mutable = self.peek() == Some("mut");
+            if mutable {
+                self.pointer += 1;
+            }
+            let parameter = match self.cmds.get(self.pointer) {
+                Some(a) if a.kind == TokenKind::Identifier => a,
+                _ => ret_err!(InvalidExpressionError::from("Parameter name was expected.").with_span(self.span_at(self.pointer))),
+            };
+            if parameters.iter().any(|a| a.name == parameter.text) {
+                ret_err!(InvalidExpressionError::new(format!("Parameter \"{}\" is bound more than once.", parameter.text)).with_span(parameter.span))
+            }
+            let binding = self.declare(&parameter.text);
+            self.pointer += 1;
+            let ty = if self.peek() == Some(":") {
+                self.pointer += 1;
+                Some(self.parse_type()?)
+            } else {
+                None
+            };
+            parameters.push(Declaration { name: parameter.text.clone(), binding, span: parameter.span, ty, mutable });
+            match self.peek() {
+                Some(",") => self.pointer += 1,
+                Some(")") => {},
+                _ => ret_err!(InvalidExpressionError::from("\",\" or \")\" was expected.").with_span(self.span_at(self.pointer))),
+            }
+        }
+        self.pointer += 1;
+        let output = if self.peek() == Some("->") {
+            self.pointer += 1;
+            Some(self.parse_type()?)
+        } else {
+            None
+        };
+        let body = self.parse_block()?;
+        Ok(Function { name, lifetimes, parameters, output, body, span })
+    }
+
+    /// 関数呼び出しを解釈する関数です。
+    fn parse_call(&mut self) -> Result<Expression, ParseError> {
+        let token = &self.cmds[self.pointer];
+        self.pointer += 2;
+        let mut arguments = Vec::new();
+        while self.peek() != Some(")") {
+            arguments.push(self.parse_expression()?);
+            match self.peek() {
+                Some(",") => self.pointer += 1,
+                Some(")") => {},
+                _ => ret_err!(InvalidExpressionError::from("\",\" or \")\" was expected.").with_span(self.span_at(self.pointer))),
+            }
+        }
+        let span = token.span.to(self.span_at(self.pointer));
+        self.pointer += 1;
+        self.calls.push((token.text.clone(), arguments.len(), span));
+        Ok(self.new_expression(span, ExpressionKind::Call(token.text.clone(), arguments)))
+    }
+
+    /// `Vec::new()` や `Box::new(a)` のような、型に関連付けられた組み込みの関数の呼び出しを解釈する関数です。
+    fn parse_path_call(&mut self) -> Result<Expression, ParseError> {
+        let start = self.span_at(self.pointer);
+        let mut path = self.cmds[self.pointer].text.clone();
+        self.pointer += 1;
+        while self.peek() == Some("::") {
+            self.pointer += 1;
+            match self.cmds.get(self.pointer) {
+                Some(a) if a.kind == TokenKind::Identifier => path = format!("{}::{}", path, a.text),
+                _ => ret_err!(InvalidExpressionError::from("Identifier was expected.").with_span(self.span_at(self.pointer))),
+            }
+            self.pointer += 1;
+        }
+        let span = start.to(self.span_at(self.pointer - 1));
+        if self.peek() != Some("(") {
+            ret_err!(InvalidExpressionError::from("\"(\" was expected.").with_span(self.span_at(self.pointer)))
+        }
+        self.pointer += 1;
+        let (mut arguments, end) = self.parse_list(")")?;
+        let count = match path.as_str() {
+            "Vec::new" => 0,
+            "Box::new" => 1,
+            _ => ret_err!(VariableNotFoundError::function(path, span)),
+        };
+        if arguments.len() != count {
+            let error = InvalidExpressionError::new(format!(
+                "Function \"{}\" takes {} argument(s) but {} argument(s) were supplied.",
+                path,
+                count,
+                arguments.len()
+            ));
+            ret_err!(error.with_span(start.to(end)))
+        }
+        let kind = match arguments.pop() {
+            Some(a) => ExpressionKind::Box(Box::new(a)),
+            None => ExpressionKind::Vec(Vec::new()),
+        };
+        Ok(self.new_expression(start.to(end), kind))
+    }
+
+    /// 閉じかっこまでの `,` で区切られた式の並びを解釈する関数です。末尾の `,` は省略できます。
+    /// 閉じかっこの範囲も返します。
+    /// * `close` - 閉じかっこ
+    fn parse_list(&mut self, close: &str) -> Result<(Vec<Expression>, Span), ParseError> {
+        let mut elements = Vec::new();
+        while self.peek() != Some(close) {
+            elements.push(self.parse_expression()?);
+            match self.peek() {
+                Some(",") => self.pointer += 1,
+                Some(a) if a == close => {},
+                _ => ret_err!(InvalidExpressionError::new(format!("\",\" or \"{}\" was expected.", close)).with_span(self.span_at(self.pointer))),
+            }
+        }
+        let end = self.span_at(self.pointer);
+        self.pointer += 1;
+        Ok((elements, end))
+    }
+
+    /// エラーの後、次の文の区切りまで要素を読み飛ばします。
+    /// 同じ深さの `;` は読み飛ばし、ブロックを閉じる `}` と次の文の始まりを表すキーワードの手前で止まります。
+    /// * `begin` - エラーとなった文の開始位置 (この位置のキーワードでは止まりません)
+    fn recover(&mut self, begin: usize) {
+        let mut depth = 0usize;
+        while let Some(a) = self.peek() {
+            match a {
+                ";" if depth == 0 => {
+                    self.pointer += 1;
+                    return;
+                },
+                "}" if depth == 0 => return,
+                "let" | "debug" | "fn" | "struct" | "#" if depth == 0 && self.pointer > begin => return,
+                "(" | "{" | "[" => depth += 1,
+                ")" | "}" | "]" => depth = depth.saturating_sub(1),
+                _ => {},
+            }
+            self.pointer += 1;
+        }
+    }
+
+    /// 式を解釈する関数です。
+    fn parse_expression(&mut self) -> Result<Expression, ParseError> {
+        self.parse_binomial(ASSIGN_PRIORITY)
+    }
+
+    /// 指定した優先順位以下の二項演算子からなる式を解釈する関数です。
+    /// * `priority` - 解釈する二項演算子の優先順位
+    fn parse_binomial(&mut self, priority: usize) -> Result<Expression, ParseError> {
+        let mut left = match priority {
+            0 => self.parse_monomial()?,
+            _ => self.parse_binomial(priority - 1)?,
+        };
+        while let Some(op) = self.peek() {
+            if ExprParser::get_priority(op) != Some(priority) {
+                break;
+            }
+            self.pointer += 1;
+            if priority == ASSIGN_PRIORITY {
+                if !left.is_place() {
+                    ret_err!(InvalidExpressionError::from("The left-hand must be variable.").with_span(left.span))
+                }
+                let right = self.parse_binomial(priority)?;
+                let span = left.span.to(right.span);
+                return Ok(self.new_expression(span, ExpressionKind::Assign(op.to_string(), Box::new(left), Box::new(right))));
+            }
+            let right = match priority {
+                0 => self.parse_monomial()?,
+                _ => self.parse_binomial(priority - 1)?,
+            };
+            let span = left.span.to(right.span);
+            left = self.new_expression(span, ExpressionKind::Binary(op.to_string(), Box::new(left), Box::new(right)));
+        }
+        Ok(left)
+    }
+
+    /// 単項演算子を含む式を解釈する関数です。
+    fn parse_monomial(&mut self) -> Result<Expression, ParseError> {
+        let op = match self.peek() {
+            Some(a) if ExprParser::is_monomial(a) => a,
+            _ => return self.parse_postfix(),
+        };
+        let op_span = self.span_at(self.pointer);
+        self.pointer += 1;
+        // `i32` の最小値は、符号を含めて一つの整数リテラルとして扱う
+        if op == "-" && self.peek().is_some_and(|a| a.parse::<u32>() == Ok(i32::MIN.unsigned_abs())) {
+            let span = op_span.to(self.span_at(self.pointer));
+            self.pointer += 1;
+            return Ok(self.new_expression(span, ExpressionKind::Integer(i32::MIN)));
+        }
+        let kind = if matches!(op, "&" | "&&") && self.peek() == Some("mut") {
+            // `&mut` を一つの単項演算子として扱う
+            self.pointer += 1;
+            BorrowKind::Mutable
+        } else {
+            BorrowKind::Shared
+        };
+        let operand = Box::new(self.parse_monomial()?);
+        let span = op_span.to(operand.span);
+        let kind = match op {
+            "&" => ExpressionKind::Reference(kind, operand),
+            "&&" => {
+                let inner_span = Span { start: op_span.start + 1, column: op_span.column + 1, ..span };
+                let inner = self.new_expression(inner_span, ExpressionKind::Reference(kind, operand));
+                ExpressionKind::Reference(BorrowKind::Shared, Box::new(inner))
+            },
+            "*" => ExpressionKind::Dereference(operand),
+            _ => ExpressionKind::Unary(op.to_string(), operand),
+        };
+        Ok(self.new_expression(span, kind))
+    }
+
+    /// フィールドへのアクセス (`a.b`、`t.0`)、メソッド呼び出し (`v.len()`)、添字によるアクセス (`a[i]`) を含む式を解釈する関数です。
+    fn parse_postfix(&mut self) -> Result<Expression, ParseError> {
+        let mut expression = self.parse_element()?;
+        loop {
+            match self.peek() {
+                Some(".") => {
+                    self.pointer += 1;
+                    let field = match self.cmds.get(self.pointer) {
+                        Some(a) if a.kind == TokenKind::Identifier => a,
+                        Some(a) if a.kind == TokenKind::Integer && a.text.bytes().all(|b| b.is_ascii_digit()) => a,
+                        _ => ret_err!(InvalidExpressionError::from("Field name was expected.").with_span(self.span_at(self.pointer))),
+                    };
+                    self.pointer += 1;
+                    if field.kind == TokenKind::Identifier && self.peek() == Some("(") {
+                        self.pointer += 1;
+                        let (arguments, end) = self.parse_list(")")?;
+                        let span = expression.span.to(end);
+                        let kind = ExpressionKind::MethodCall(Box::new(expression), field.text.clone(), field.span, arguments);
+                        expression = self.new_expression(span, kind);
+                        continue;
+                    }
+                    let span = expression.span.to(field.span);
+                    expression = self.new_expression(span, ExpressionKind::Field(Box::new(expression), field.text.clone(), field.span));
+                },
+                Some("[") => {
+                    self.pointer += 1;
+                    let index = self.parse_expression()?;
+                    if self.peek() != Some("]") {
+                        ret_err!(InvalidExpressionError::from("\"]\" was expected.").with_span(self.span_at(self.pointer)))
+                    }
+                    let span = expression.span.to(self.span_at(self.pointer));
+                    self.pointer += 1;
+                    expression = self.new_expression(span, ExpressionKind::Index(Box::new(expression), Box::new(index)));
+                },
+                _ => return Ok(expression),
+            }
+        }
+    }
+
+    /// `{` から対応する `}` までをブロックとして解釈する関数です。
+    fn parse_block(&mut self) -> Result<Block, ParseError> {
+        let start = self.span_at(self.pointer);
+        if self.peek() != Some("{") {
+            ret_err!(InvalidExpressionError::from("\"{\" was expected.").with_span(start))
+        }
+        self.pointer += 1;
+        self.scopes.push(HashMap::new());
+        let block = self.parse_sentence(start);
+        self.scopes.pop();
+        if self.peek() != Some("}") {
+            ret_err!(InvalidExpressionError::from("\"}\" was expected.").with_span(self.span_at(self.pointer)))
+        }
+        self.pointer += 1;
+        Ok(block)
+    }
+
+    /// `if` 式を解釈する関数です。`else if` は `else` 節の中の `if` 式として扱います。
+    fn parse_if(&mut self) -> Result<Expression, ParseError> {
+        let start = self.span_at(self.pointer);
+        self.pointer += 1;
+        let condition = self.parse_expression()?;
+        let then = self.parse_block()?;
+        let mut span = start.to(then.span);
+        let otherwise = if self.peek() == Some("else") {
+            self.pointer += 1;
+            let expression = if self.peek() == Some("if") {
+                self.parse_if()?
+            } else {
+                let block = self.parse_block()?;
+                self.new_expression(block.span, ExpressionKind::Block(block))
+            };
+            span = span.to(expression.span);
+            Some(Box::new(expression))
+        } else {
+            None
+        };
+        Ok(self.new_expression(span, ExpressionKind::If(Box::new(condition), then, otherwise)))
+    }
+
+    /// `while` または `loop` によるループを解釈する関数です。
+    fn parse_loop(&mut self) -> Result<Expression, ParseError> {
+        let start = self.span_at(self.pointer);
+        let is_while = self.peek() == Some("while");
+        self.pointer += 1;
+        let condition = if is_while { Some(self.parse_expression()?) } else { None };
+        self.loops += 1;
+        let body = self.parse_block();
+        self.loops -= 1;
+        let body = body?;
+        let span = start.to(body.span);
+        let kind = match condition {
+            Some(a) => ExpressionKind::While(Box::new(a), body),
+            None => ExpressionKind::Loop(body),
+        };
+        Ok(self.new_expression(span, kind))
+    }
+
+    /// 即値、変数、かっこ、タプル式、配列式、ブロック、構造体式を解釈する関数です。
+    fn parse_element(&mut self) -> Result<Expression, ParseError> {
+        let token = match self.cmds.get(self.pointer) {
+            Some(a) => a,
+            None => ret_err!(InvalidExpressionError::from("Expression was expected.").with_span(self.span_at(self.pointer))),
+        };
+        let kind = match (token.kind, token.text.as_str()) {
+            // `()` は要素のないタプル式 (ユニット値) とする
+            (_, "(") if self.cmds.get(self.pointer + 1).is_some_and(|a| a.text == ")") => {
+                let end = self.cmds[self.pointer + 1].span;
+                self.pointer += 2;
+                return Ok(self.new_expression(token.span.to(end), ExpressionKind::Tuple(Vec::new())));
+            },
+            (_, "(") => {
+                self.pointer += 1;
+                let expression = self.parse_expression()?;
+                // `,` が続けばタプル式とし、`(a,)` は要素が一つのタプルとする
+                if self.peek() == Some(",") {
+                    self.pointer += 1;
+                    let (mut elements, end) = self.parse_list(")")?;
+                    elements.insert(0, expression);
+                    return Ok(self.new_expression(token.span.to(end), ExpressionKind::Tuple(elements)));
+                }
+                if self.peek() != Some(")") {
+                    ret_err!(InvalidExpressionError::from("\")\" was expected.").with_span(self.span_at(self.pointer)))
+                }
+                self.pointer += 1;
+                return Ok(expression);
+            },
+            (_, "[") => {
+                self.pointer += 1;
+                let (elements, end) = self.parse_list("]")?;
+                return Ok(self.new_expression(token.span.to(end), ExpressionKind::Array(elements)));
+            },
+            (_, "{") => {
+                let block = self.parse_block()?;
+                return Ok(self.new_expression(block.span, ExpressionKind::Block(block)));
+            },
+            (TokenKind::Keyword, "if") => return self.parse_if(),
+            (TokenKind::Keyword, "while" | "loop") => return self.parse_loop(),
+            (TokenKind::Keyword, "return") => {
+                if !self.in_function {
+                    ret_err!(InvalidExpressionError::from("\"return\" outside of a function.").with_span(token.span))
+                }
+                self.pointer += 1;
+                let value = match self.peek() {
+                    Some(";" | "}") | None => None,
+                    Some(_) => Some(Box::new(self.parse_expression()?)),
+                };
+                let span = value.as_ref().map_or(token.span, |a| token.span.to(a.span));
+                return Ok(self.new_expression(span, ExpressionKind::Return(value)));
+            },
+            (TokenKind::Identifier, _) if self.cmds.get(self.pointer + 1).is_some_and(|a| a.text == "(") => {
+                return self.parse_call();
+            },
+            (TokenKind::Identifier, _) if self.cmds.get(self.pointer + 1).is_some_and(|a| a.text == "::") => {
+                return self.parse_path_call();
+            },
+            (TokenKind::Identifier, "vec") if self.cmds.get(self.pointer + 1).is_some_and(|a| a.text == "!") => {
+                self.pointer += 2;
+                if self.peek() != Some("[") {
+                    ret_err!(InvalidExpressionError::from("\"[\" was expected.").with_span(self.span_at(self.pointer)))
+                }
+                self.pointer += 1;
+                let (elements, end) = self.parse_list("]")?;
+                return Ok(self.new_expression(token.span.to(end), ExpressionKind::Vec(elements)));
+            },
+            (TokenKind::Identifier, a) if self.struct_names.contains(a) && self.cmds.get(self.pointer + 1).is_some_and(|a| a.text == "{") => {
+                return self.parse_struct_literal();
+            },
+            (TokenKind::Keyword, a @ ("break" | "continue")) => {
+                if self.loops == 0 {
+                    ret_err!(InvalidExpressionError::new(format!("\"{}\" outside of a loop.", a)).with_span(token.span))
+                }
+                self.pointer += 1;
+                if a == "continue" {
+                    return Ok(self.new_expression(token.span, ExpressionKind::Continue));
+                }
+                let value = match self.peek() {
+                    Some(";" | "}") | None => None,
+                    Some(_) => Some(Box::new(self.parse_expression()?)),
+                };
+                let span = value.as_ref().map_or(token.span, |a| token.span.to(a.span));
+                return Ok(self.new_expression(span, ExpressionKind::Break(value)));
+            },
+            (TokenKind::Integer, a) => match a.parse() {
+                Ok(i) => ExpressionKind::Integer(i),
+                Err(_) if a.bytes().all(|b| b.is_ascii_digit()) => {
+                    ret_err!(InvalidExpressionError::new(format!("Integer literal \"{}\" is out of range for \"i32\".", a)).with_span(token.span))
+                },
+                Err(_) => ret_err!(InvalidExpressionError::new(format!("Invalid integer literal \"{}\".", a)).with_span(token.span)),
+            },
+            (TokenKind::String, a) => ExpressionKind::String(a.trim_matches('"').to_string()),
+            (TokenKind::Identifier, a) => match self.scopes.iter().rev().find_map(|s| s.get(a).copied()) {
+                Some(binding) => ExpressionKind::Variable(Variable { name: a.to_string(), binding }),
+                None => ret_err!(VariableNotFoundError::new(a.to_string(), token.span)),
+            },
+            (_, a) => ret_err!(InvalidExpressionError::new(format!("Illegal operator \"{}\".", a)).with_span(token.span)),
+        };
+        self.pointer += 1;
+        Ok(self.new_expression(token.span, kind))
+    }
+}
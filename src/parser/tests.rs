@@ -0,0 +1,322 @@
+use super::ExprParser;
+use super::explain::explain;
+use super::variables::VarType;
+
+/// プログラムを解釈し、報告されたエラーのエラーコードを報告された順に返します。
+/// 関数呼び出しの深さの上限まで評価できるよう、主スレッドと同じ大きさのスタックを持つスレッドで解釈します。
+/// - `source` - 解釈するプログラム
+fn codes(source: &str) -> Vec<&'static str> {
+    let source = source.to_string();
+    std::thread::Builder::new()
+        .stack_size(8 * 1024 * 1024)
+        .spawn(move || match ExprParser::new().parse(&source) {
+            Ok(_) => Vec::new(),
+            Err(errors) => errors.iter().map(|a| a.code()).collect(),
+        })
+        .unwrap()
+        .join()
+        .unwrap()
+}
+
+/// プログラムを解釈し、メインのブロックの値を返します。エラーが報告された場合はテストを失敗させます。
+/// - `source` - 解釈するプログラム
+fn value(source: &str) -> VarType {
+    match ExprParser::new().parse(source) {
+        Ok(a) => a,
+        Err(errors) => panic!("{:?}", errors.iter().map(|a| a.to_string()).collect::<Vec<String>>()),
+    }
+}
+
+/// 説明の "Erroneous code example:" に続く、字下げされたコード例を取り出します。
+/// - `explanation` - エラーコードの説明
+fn example(explanation: &str) -> String {
+    explanation
+        .lines()
+        .skip_while(|a| *a != "Erroneous code example:")
+        .skip(1)
+        .skip_while(|a| a.is_empty())
+        .take_while(|a| a.is_empty() || a.starts_with("    "))
+        .map(|a| a.strip_prefix("    ").unwrap_or(a))
+        .collect::<Vec<&str>>()
+        .join("\n")
+}
+
+#[test]
+fn explain_examples_report_their_own_code() {
+    let mut count = 0;
+    while let Some(explanation) = explain(&format!("E{:04}", count + 1)) {
+        count += 1;
+        let code = format!("E{:04}", count);
+        let found = codes(&example(explanation));
+        assert!(!found.is_empty(), "the example of {} reported no error", code);
+        assert!(found.iter().all(|a| *a == code), "the example of {} reported {:?}", code, found);
+    }
+    assert_eq!(count, 34);
+}
+
+#[test]
+fn conflicting_borrows() {
+    assert_eq!(codes("let mut a = 1;\nlet b = &mut a;\nlet c = &mut a;\n*b += 1;"), ["E0006"]);
+    assert_eq!(codes("fn f(a: &mut i32, b: &i32) {}\nlet mut a = 1;\nf(&mut a, &a);"), ["E0007"]);
+    assert_eq!(codes("let mut a = 1;\nlet m = &mut a;\nlet x = &mut *m;\nlet y = &mut *m;\n*x += 1;"), ["E0006"]);
+}
+
+#[test]
+fn using_a_mutably_borrowed_value() {
+    assert_eq!(codes("let mut a = 1;\nlet r = &mut a;\nlet b = a;\n*r = 1;"), ["E0030"]);
+    assert_eq!(codes("let mut a = 1;\nlet r = &mut a;\nif a > 0 { *r = 3 }"), ["E0030"]);
+    assert_eq!(codes("let mut a = 1;\nlet m = &mut a;\nlet x = &mut *m;\nlet y = *m;\n*x += 1;"), ["E0030"]);
+}
+
+#[test]
+fn assigning_to_a_borrowed_value() {
+    assert_eq!(codes("let mut a = 1;\nlet r = &a;\na = 2;\ndebug *r;"), ["E0031"]);
+    assert_eq!(codes("let mut a = 1;\nlet r = &a;\na += 1;\ndebug *r;"), ["E0031"]);
+    assert_eq!(codes("let mut a = 1;\nlet m = &mut a;\nlet r = &*m;\n*m = 2;\ndebug *r;"), ["E0031"]);
+}
+
+#[test]
+fn borrows_end_at_their_last_use() {
+    let source = "
+        let mut a = 1;
+        let r = &mut a;
+        *r += 1;
+        let m = &mut a;
+        let x = &mut *m;
+        *x += 1;
+        *m += 1;
+        let s = &a;
+        a = *s + a;
+        let t;
+        { let q = &mut a; t = &mut *q; }
+        *t += 1;
+        a
+    ";
+    assert_eq!(value(source), VarType::Integer(9));
+}
+
+#[test]
+fn references_observe_writes_to_the_place() {
+    assert_eq!(value("let mut a = 1;\nlet p = &mut a;\n*p = 2;\na"), VarType::Integer(2));
+    assert_eq!(value("let mut a = 1;\nlet p = &mut a;\n*p += 1;\nlet q = &a;\n*q + a"), VarType::Integer(4));
+    assert_eq!(value("let mut t = (1, 2);\nlet p = &mut t;\np.0 = 5;\nlet q = &t.0;\n*q + t.1"), VarType::Integer(7));
+}
+
+#[test]
+fn integer_literal_out_of_range() {
+    assert_eq!(codes("let a = 99999999999;\ndebug a;"), ["E0003"]);
+    assert_eq!(codes("let a = 2147483648;"), ["E0003"]);
+    assert_eq!(value("let a = -2147483648;\na + 2147483647"), VarType::Integer(-1));
+}
+
+#[test]
+fn labels_align_under_full_width_characters() {
+    let source = "let s = \"日本語\"; let t = s; let u = s;";
+    let errors = ExprParser::new().parse(source).unwrap_err();
+    let rendered = errors[0].diagnostic().render("main.rs", source);
+    assert!(rendered.contains("\n  |                           -          ^ value used here after move\n"), "{}", rendered);
+}
+
+#[test]
+fn missing_semicolon_after_let_keeps_the_binding() {
+    assert_eq!(codes("let a = 1\nlet b = 2;\ndebug a + b;"), ["E0003"]);
+}
+
+#[test]
+fn mismatched_closing_bracket_is_reported_once() {
+    assert_eq!(codes("{ let c = ); }"), ["E0002"]);
+}
+
+#[test]
+fn errors_in_separate_statements_are_all_reported() {
+    assert_eq!(codes("let a = 1 +;\nlet b = c;\nlet d = 2\ndebug a + b + d;"), ["E0003", "E0001", "E0003"]);
+}
+
+#[test]
+fn loops_produce_break_values() {
+    assert_eq!(value("let mut i = 0;\nlet n = loop { i += 1; if i == 5 { break i * 10; } };\nn"), VarType::Integer(50));
+    let source = "
+        let mut i = 0;
+        let mut sum = 0;
+        while i < 10 {
+            i += 1;
+            if i % 2 == 0 { continue; }
+            sum += i;
+        }
+        sum
+    ";
+    assert_eq!(value(source), VarType::Integer(25));
+}
+
+#[test]
+fn value_moved_in_previous_iteration() {
+    let errors = ExprParser::new().parse("let s = \"a\";\nloop { let t = s; }").unwrap_err();
+    assert_eq!(errors.iter().map(|a| a.code()).collect::<Vec<&str>>(), ["E0005"]);
+    assert!(errors[0].diagnostic().render("main.rs", "").contains("in previous iteration of loop"));
+}
+
+#[test]
+fn unannotated_parameter_is_moved() {
+    assert_eq!(codes("fn f(s) { let t = s; let u = s; }\nf(\"a\");"), ["E0005"]);
+}
+
+#[test]
+fn integer_operands_of_unannotated_parameters_are_copied() {
+    assert_eq!(value("fn f(n) { n * n - n }\nf(3)"), VarType::Integer(6));
+}
+
+#[test]
+fn mutable_reference_arguments_are_reborrowed() {
+    assert_eq!(value("fn inc(p: &mut i32) { *p += 1 }\nlet mut x = 0;\nlet r = &mut x;\ninc(r);\ninc(r);\n*r += 1;\nx"), VarType::Integer(3));
+    assert_eq!(codes("fn both(p: &mut i32, q: &mut i32) { *p += *q }\nlet mut x = 1;\nlet r = &mut x;\nboth(r, r);"), ["E0006"]);
+}
+
+#[test]
+fn returned_reference_must_have_the_output_lifetime() {
+    let source = "fn f<'a, 'b>(x: &'a i32, y: &'b i32) -> &'a i32 { y }\nlet a = 1;\nlet r;\n{ let b = 2; r = f(&a, &b); }\ndebug *r;";
+    assert_eq!(codes(source), ["E0032"]);
+    assert_eq!(codes("fn f<'a>(x: &'a i32, y: &i32) -> &'a i32 { if *y > 0 { return &*y; } x }"), ["E0032"]);
+    assert_eq!(codes("fn f(x: &i32) -> &'static i32 { x }"), ["E0032"]);
+    assert_eq!(value("fn f<'a, 'b>(x: &'a i32, y: &'b i32) -> &'a i32 { if *y > 0 { &*x } else { x } }\nlet a = 1;\n*f(&a, &2)"), VarType::Integer(1));
+}
+
+#[test]
+fn operation_on_void_found_while_running() {
+    assert_eq!(codes("fn log(n) { debug n; }\nlog(1) + 1"), ["E0004"]);
+}
+
+#[test]
+fn struct_literal_with_a_duplicate_field_is_reported_once() {
+    assert_eq!(codes("struct P { x: i32, y: i32 }\nlet p = P { x: 1, x: 2, y: 3 };\ndebug p.y;"), ["E0003"]);
+}
+
+#[test]
+fn missing_field_names_the_inferred_type() {
+    let source = "let t = [1];\nlet v = t.x;";
+    let errors = ExprParser::new().parse(source).unwrap_err();
+    assert!(errors[0].diagnostic().render("main.rs", source).contains("no field `x` on type `[i32; 1]`"));
+}
+
+#[test]
+fn recursive_struct_has_infinite_size() {
+    assert_eq!(codes("struct S { a: S }"), ["E0033"]);
+    assert_eq!(codes("struct T { b: (i32, [U; 2]) }\nstruct U { t: T }"), ["E0033"]);
+    assert_eq!(codes("struct N { next: Box<N>, children: Vec<N> }"), Vec::<&str>::new());
+}
+
+#[test]
+fn unit_literal() {
+    assert_eq!(value("let t = ();\nt"), VarType::Void);
+    assert_eq!(value("fn f() -> () { () }\nlet c = (f(), 1);\nc.1"), VarType::Integer(1));
+}
+
+#[test]
+fn tuple_and_array_elements_are_read() {
+    assert_eq!(value("let t = (1, (2, 3));\nt.0 + t.1.1"), VarType::Integer(4));
+    assert_eq!(value("let a = [[1, 2], [3, 4]];\nlet i = 1;\na[i][0] * 10 + a[0][i]"), VarType::Integer(32));
+    assert_eq!(value("let a = [\"x\", \"y\"];\nlet r = &a[1];\nlet t = (r, 5);\n*t.0 == \"y\""), VarType::Integer(1));
+}
+
+#[test]
+fn moving_an_element_out_of_an_array() {
+    assert_eq!(codes("let a = [\"x\", \"y\"];\nlet s = a[0];"), ["E0025"]);
+    assert_eq!(codes("let v = vec![\"x\"];\nlet i = 0;\nlet s = v[i];"), ["E0025"]);
+}
+
+#[test]
+fn borrows_through_a_reference_conflict_with_methods() {
+    assert_eq!(codes("fn f(v: &mut Vec<i32>) -> i32 { let r = &v[0]; v.pop(); *r }"), ["E0007"]);
+    assert_eq!(codes("let mut v = vec![1, 2];\nlet m = &mut v;\nlet r = &m[0];\nm.pop();\ndebug *r;"), ["E0007"]);
+    assert_eq!(codes("let mut v = vec![1];\nlet m = &mut v;\nlet x = &mut *m;\nm.push(1);\nx.push(2);"), ["E0006"]);
+    assert_eq!(value("let mut v = vec![1, 2];\nlet m = &mut v;\nm.push(3);\nlet r = &m[0];\nlet n = m.len();\n*r + n"), VarType::Integer(4));
+}
+
+#[test]
+fn assigning_to_a_place_borrowed_by_an_element() {
+    assert_eq!(codes("let mut v = vec![1, 2];\nlet r = &v[0];\nv = Vec::new();\ndebug *r;"), ["E0031"]);
+    assert_eq!(codes("let mut t = (vec![1], 2);\nlet r = &t.0[0];\nt.0 = Vec::new();\ndebug *r;"), ["E0031"]);
+}
+
+#[test]
+fn popping_from_an_empty_vec() {
+    assert_eq!(codes("let mut v = vec![1];\nv.pop();\nv.pop();"), ["E0034"]);
+    assert_eq!(codes("let v = vec![1];\nlet i = 1;\ndebug v[i];"), ["E0024"]);
+}
+
+#[test]
+fn dereferencing_a_box_moves_it() {
+    assert_eq!(codes("let a = Box::new(\"s\");\nlet t = *a;\ndebug a;"), ["E0005"]);
+    assert_eq!(value("let a = Box::new(1);\nlet t = *a;\n*a + t"), VarType::Integer(2));
+}
+
+#[test]
+fn values_are_dropped_in_reverse_declaration_order() {
+    let source = "
+        let a = Box::new(1);
+        let b = \"b\";
+        let c = b;
+        {
+            let d = vec![1];
+            drop(a);
+            debug 0;
+        }
+        let e = (2, \"e\");
+        debug 1;
+    ";
+    let mut parser = ExprParser::new();
+    parser.parse(source).unwrap();
+    let expected = [
+        "Drop(a): Box(Integer(1))",
+        "Integer(0)",
+        "Drop(d): Vec([Integer(1)])",
+        "Integer(1)",
+        "Drop(e): Tuple([Integer(2), String(\"e\")])",
+        "Drop(c): String(\"b\")",
+    ];
+    assert_eq!(parser.output, expected);
+}
+
+#[test]
+fn moving_out_from_behind_a_reference() {
+    assert_eq!(codes("let s = \"x\";\nlet r = &s;\nlet t = *r;\ndebug s;"), ["E0029"]);
+    assert_eq!(codes("struct P { name: String }\nlet p = P { name: \"n\" };\nlet r = &p;\nlet n = r.name;"), ["E0029"]);
+    assert_eq!(value("let a = 1;\nlet r = &a;\nlet b = *r;\na + b"), VarType::Integer(2));
+}
+
+#[test]
+fn copy_struct_with_a_string_field() {
+    assert_eq!(codes("#[derive(Clone, Copy)]\nstruct N { id: i32, name: String }"), ["E0028"]);
+    let source = "#[derive(Clone, Copy)]\nstruct N { id: i32, r: &'static i32 }\nlet a = N { id: 1, r: &2 };\nlet b = a;\na.id + *b.r";
+    assert_eq!(value(source), VarType::Integer(3));
+}
+
+#[test]
+fn valid_program_has_no_errors() {
+    let source = "
+        struct P { name: String, b: Box<i32> }
+        fn fact(n) { if n <= 1 { return 1; } n * fact(n - 1) }
+        let p = P { name: \"p\", b: Box::new(1) };
+        let r = &p;
+        let n = r.name.clone();
+        let mut v = vec![fact(5)];
+        v.push(*p.b);
+        v.len() * 1000 + v[0]
+    ";
+    assert_eq!(value(source), VarType::Integer(2120));
+}
+
+#[test]
+fn cloned_values_are_independent() {
+    assert_eq!(value("let s = \"a\";\nlet t = s.clone() + \"b\";\ns == \"a\" && t == \"ab\""), VarType::Integer(1));
+    assert_eq!(value("let mut v = vec![1];\nlet mut w = v.clone();\nw.push(2);\nv.len() * 10 + w.len()"), VarType::Integer(12));
+    assert_eq!(value("let a = Box::new(3);\nlet b = a.clone();\ndrop(a);\n*b"), VarType::Integer(3));
+    assert_eq!(value("struct P { name: String }\nlet p = P { name: \"x\" };\nlet q = p.clone();\ndrop(p);\nq.name == \"x\""), VarType::Integer(1));
+}
+
+#[test]
+fn use_after_move_suggests_clone() {
+    let source = "let s = \"a\";\nlet t = s;\ndebug s;";
+    let errors = ExprParser::new().parse(source).unwrap_err();
+    assert_eq!(errors.iter().map(|a| a.code()).collect::<Vec<&str>>(), ["E0005"]);
+    assert!(errors[0].diagnostic().render("main.rs", source).contains("`s.clone()`"));
+}
@@ -0,0 +1,84 @@
+use std::fmt::Display;
+
+/// ソースコード上の範囲を表す構造体です。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Span {
+    /// 範囲の開始位置 (バイト単位)
+    pub start: usize,
+    /// 範囲の終了位置 (バイト単位、この位置を含まない)
+    pub end: usize,
+    /// 開始位置の行番号 (1 から始まる)
+    pub line: usize,
+    /// 開始位置の列番号 (1 から始まる文字単位)
+    pub column: usize,
+}
+
+impl Span {
+    /// 自身の開始位置から `other` の終了位置までの範囲を返します。
+    /// - `other` - 範囲の終わりとなる範囲
+    pub fn to(&self, other: Span) -> Span {
+        Span { end: other.end.max(self.end), ..*self }
+    }
+}
+
+impl Display for Span {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
+/// 要素の種類を定義します。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenKind {
+    /// 変数名などの識別子を表します。
+    Identifier,
+    /// 整数リテラルを表します。
+    Integer,
+    /// 文字列リテラルを表します。
+    String,
+    /// 演算子やかっこなどの記号を表します。
+    Punctuation,
+    /// 予約語を表します。
+    Keyword,
+    /// 寿命 (`'a`) を表します。
+    Lifetime,
+}
+
+/// 予約語の一覧です。
+pub const KEYWORDS: [&str; 12] = [
+    "let", "debug", "mut", "if", "else", "while", "loop", "break", "continue", "fn", "return", "struct",
+];
+
+/// 入力を分割した要素を表す構造体です。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Token {
+    /// 要素の種類
+    pub kind: TokenKind,
+    /// 要素の文字列
+    pub text: String,
+    /// 要素のソースコード上の範囲
+    pub span: Span,
+}
+
+impl Token {
+    /// 文字列から要素の種類を判定して要素を作成します。
+    /// 数字で始まる要素は整数リテラルとし、値が `i32` の範囲に収まるかは構文解析で確認します。
+    /// - `text` - 要素の文字列
+    /// - `span` - 要素のソースコード上の範囲
+    pub fn new(text: String, span: Span) -> Token {
+        let kind = if text.starts_with('"') {
+            TokenKind::String
+        } else if text.starts_with(|c: char| c.is_ascii_digit()) {
+            TokenKind::Integer
+        } else if KEYWORDS.contains(&text.as_str()) {
+            TokenKind::Keyword
+        } else if text.starts_with('\'') && text.len() > 1 {
+            TokenKind::Lifetime
+        } else if text.starts_with(|c: char| c != '_' && c.is_ascii_punctuation()) {
+            TokenKind::Punctuation
+        } else {
+            TokenKind::Identifier
+        };
+        Token { kind, text, span }
+    }
+}
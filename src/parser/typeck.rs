@@ -0,0 +1,727 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt::Display;
+
+use log::{info, trace};
+
+use crate::ret_err;
+
+use super::ast::{Block, Declaration, Expression, ExpressionKind, FieldDefinition, FieldValue, Function, Program, Statement, Struct, Type, TypeKind};
+use super::borrow::{self, BorrowKind};
+use super::errors::{
+    CopyError, FieldError, IndexError, InvalidExpressionError, InvalidOperandError, MethodError, MismatchedTypesError, ParseError, RecursiveTypeError, ReferenceError,
+    VariableNotFoundError,
+};
+use super::token::Span;
+
+/// 型検査で扱う型を定義します。
+#[derive(Debug, Clone, PartialEq)]
+enum Ty {
+    /// 型注釈のない引数や戻り値のように、型が分からないことを表します。どの型とも一致するとみなします。
+    Unknown,
+    /// 値に到達しない (`return` や `break` の) 型 (`!`) を表します。どの型とも一致するとみなします。
+    Never,
+    /// 値がないこと (`()`) を表します。
+    Unit,
+    /// 整数 (`i32`) を表します。
+    Integer,
+    /// 文字列 (`String`) を表します。`str` も同じ型として扱います。
+    String,
+    /// 参照を表します。
+    Reference(BorrowKind, Box<Ty>),
+    /// 構造体を、構造体名で表します。
+    Struct(String),
+    /// 要素が一つ以上のタプルを表します。
+    Tuple(Vec<Ty>),
+    /// 配列を、要素の型と要素の数で表します。
+    Array(Box<Ty>, usize),
+    /// 可変長の配列 (`Vec<T>`) を表します。
+    Vec(Box<Ty>),
+    /// ヒープ上の値を所有するポインタ (`Box<T>`) を表します。
+    Box(Box<Ty>),
+    /// 型注釈のない変数の、推論中の型を表します。
+    Variable(usize),
+}
+
+impl Ty {
+    /// 具体的な型が分かっているかを取得します。
+    fn is_known(&self) -> bool {
+        !matches!(self, Ty::Unknown | Ty::Never | Ty::Variable(_))
+    }
+}
+
+impl Display for Ty {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Ty::Unknown | Ty::Variable(_) => write!(f, "_"),
+            Ty::Never => write!(f, "!"),
+            Ty::Unit => write!(f, "()"),
+            Ty::Integer => write!(f, "i32"),
+            Ty::String => write!(f, "String"),
+            Ty::Reference(BorrowKind::Shared, a) => write!(f, "&{}", a),
+            Ty::Reference(BorrowKind::Mutable, a) => write!(f, "&mut {}", a),
+            Ty::Struct(a) => write!(f, "{}", a),
+            Ty::Tuple(a) if a.len() == 1 => write!(f, "({},)", a[0]),
+            Ty::Tuple(a) => write!(f, "({})", a.iter().map(|a| a.to_string()).collect::<Vec<String>>().join(", ")),
+            Ty::Array(a, length) => write!(f, "[{}; {}]", a, length),
+            Ty::Vec(a) => write!(f, "Vec<{}>", a),
+            Ty::Box(a) => write!(f, "Box<{}>", a),
+        }
+    }
+}
+
+/// 関数の引数と戻り値の型を保持する構造体です。
+#[derive(Debug, Clone)]
+struct Signature {
+    /// 引数の型
+    parameters: Vec<Ty>,
+    /// 戻り値の型 (省略されていれば不明)
+    output: Ty,
+}
+
+/// 型の単一化に失敗したときに、推論された型の由来となった値の範囲を保持する構造体です。
+#[derive(Debug, Default)]
+struct Conflict {
+    /// 期待される型が推論された値の範囲
+    expected_at: Option<Span>,
+    /// 使用された値の型が推論された値の範囲
+    found_at: Option<Span>,
+}
+
+/// 構文木の各式の型を求め、型の不一致を実行前に検査する構造体です。
+/// 型注釈のない変数の型は、代入される値などとの単一化によって推論します。
+/// 型注釈のない引数と関数の戻り値の型は不明とし、どの型の値とも一致するとみなします。
+pub struct TypeChecker {
+    /// 変数ごとの型
+    bindings: HashMap<usize, Ty>,
+    /// 推論中の型ごとの、推論された型とその由来となった値の範囲
+    substitution: Vec<Option<(Ty, Span)>>,
+    /// 関数ごとの引数と戻り値の型
+    functions: HashMap<String, Signature>,
+    /// 構造体ごとの、定義された順のフィールド名と型
+    structs: HashMap<String, Vec<(String, Ty)>>,
+    /// 検査中の関数の戻り値の型 (関数の外であれば `None`)
+    output: Option<Ty>,
+    /// 検査中のループの `break` の値の型 (`while` であれば `None`)
+    loops: Vec<Option<Ty>>,
+    errors: Vec<ParseError>,
+}
+
+impl TypeChecker {
+    /// 構文木全体の型を検査します。
+    /// - `program` - 検査するプログラム
+    pub fn check(program: &Program) -> Result<(), Vec<ParseError>> {
+        info!("Start type checking...");
+        let mut checker = TypeChecker {
+            bindings: HashMap::new(),
+            substitution: Vec::new(),
+            functions: HashMap::new(),
+            structs: HashMap::new(),
+            output: None,
+            loops: Vec::new(),
+            errors: Vec::new(),
+        };
+        // フィールドの型は他の構造体を含むことがあるため、先にすべての構造体名を登録する
+        let mut structs: Vec<&Struct> = program.structs.values().collect();
+        structs.sort_by_key(|a| a.span.start);
+        for a in &structs {
+            checker.structs.insert(a.name.clone(), Vec::new());
+        }
+        // 自身を直接含む構造体は大きさが決まらないため、循環ごとに一度だけ報告する
+        let mut recursive: HashSet<&str> = HashSet::new();
+        for a in &structs {
+            let Some(cycle) = Self::find_cycle(&a.name, a, &program.structs, &mut HashSet::new()) else {
+                continue;
+            };
+            if cycle.iter().all(|(b, _)| recursive.insert(b)) {
+                let names = cycle.iter().map(|(b, _)| b.to_string()).collect();
+                checker.errors.push(RecursiveTypeError::new(names, a.span, cycle.iter().map(|(_, b)| b.span).collect()).into());
+            }
+        }
+        for a in structs {
+            let fields = a.fields.iter().map(|b| (b.name.clone(), checker.resolve_or_unknown(Some(&b.ty)))).collect();
+            checker.structs.insert(a.name.clone(), fields);
+            // `Copy` の構造体は、すべてのフィールドが `Copy` である必要がある
+            if let Some(span) = a.copy {
+                let fields: Vec<Span> = a.fields.iter().filter(|b| !borrow::is_copy(&b.ty, &program.structs)).map(|b| b.span).collect();
+                if !fields.is_empty() {
+                    checker.errors.push(CopyError::new(a.name.clone(), span, fields).into());
+                }
+            }
+        }
+        let mut definitions: Vec<&Function> = program.functions.values().collect();
+        definitions.sort_by_key(|a| a.span.start);
+        for a in &definitions {
+            let parameters = a.parameters.iter().map(|b| checker.resolve_or_unknown(b.ty.as_ref())).collect();
+            let output = checker.resolve_or_unknown(a.output.as_ref());
+            checker.functions.insert(a.name.clone(), Signature { parameters, output });
+        }
+        for a in definitions {
+            checker.check_function(a);
+        }
+        checker.output = None;
+        checker.check_block(&program.main);
+        if checker.errors.is_empty() {
+            Ok(())
+        } else {
+            Err(checker.errors)
+        }
+    }
+
+    /// 構造体が、参照や `Box`、`Vec` を介さずに自身を含むかを調べます。
+    /// 自身を含めば、循環をたどるフィールドを、フィールドを持つ構造体名とともに返します。
+    /// - `name` - 自身を含むかを調べる構造体名
+    /// - `current` - 調べている構造体
+    /// - `structs` - 構造体名ごとの構造体の定義
+    /// - `visited` - 調べ終えた構造体名
+    fn find_cycle<'p>(
+        name: &str,
+        current: &'p Struct,
+        structs: &'p HashMap<String, Struct>,
+        visited: &mut HashSet<&'p str>,
+    ) -> Option<Vec<(&'p str, &'p FieldDefinition)>> {
+        for field in &current.fields {
+            for a in Self::inline_structs(&field.ty) {
+                let mut cycle = if a == name {
+                    Vec::new()
+                } else {
+                    let Some(next) = structs.get(a).filter(|b| visited.insert(&b.name)) else {
+                        continue;
+                    };
+                    match Self::find_cycle(name, next, structs, visited) {
+                        Some(b) => b,
+                        None => continue,
+                    }
+                };
+                cycle.insert(0, (current.name.as_str(), field));
+                return Some(cycle);
+            }
+        }
+        None
+    }
+
+    /// 型注釈の値が、ポインタを介さずにその場に格納する構造体名を列挙します。
+    /// - `ty` - 型注釈
+    fn inline_structs(ty: &Type) -> Vec<&str> {
+        match &ty.kind {
+            TypeKind::Named(a) => vec![a.as_str()],
+            TypeKind::Tuple(a) => a.iter().flat_map(Self::inline_structs).collect(),
+            TypeKind::Array(a, _) => Self::inline_structs(a),
+            TypeKind::Reference(_, _, _) | TypeKind::Vec(_) | TypeKind::Box(_) => Vec::new(),
+        }
+    }
+
+    /// 型注釈から型を求めます。
+    /// - `ty` - 型注釈
+    fn resolve(&self, ty: &Type) -> Result<Ty, ParseError> {
+        match &ty.kind {
+            TypeKind::Named(a) if a == "i32" => Ok(Ty::Integer),
+            TypeKind::Named(a) if a == "String" || a == "str" => Ok(Ty::String),
+            TypeKind::Named(a) if self.structs.contains_key(a) => Ok(Ty::Struct(a.clone())),
+            TypeKind::Named(a) => ret_err!(VariableNotFoundError::type_name(a.clone(), ty.span)),
+            TypeKind::Reference(_, kind, a) => Ok(Ty::Reference(*kind, Box::new(self.resolve(a)?))),
+            TypeKind::Tuple(a) if a.is_empty() => Ok(Ty::Unit),
+            TypeKind::Tuple(a) => Ok(Ty::Tuple(a.iter().map(|a| self.resolve(a)).collect::<Result<_, _>>()?)),
+            TypeKind::Array(a, length) => Ok(Ty::Array(Box::new(self.resolve(a)?), *length)),
+            TypeKind::Vec(a) => Ok(Ty::Vec(Box::new(self.resolve(a)?))),
+            TypeKind::Box(a) => Ok(Ty::Box(Box::new(self.resolve(a)?))),
+        }
+    }
+
+    /// 型注釈から型を求めます。型注釈がないか、型が見つからなければ不明とし、エラーを記録します。
+    /// - `ty` - 型注釈
+    fn resolve_or_unknown(&mut self, ty: Option<&Type>) -> Ty {
+        match ty.map(|a| self.resolve(a)) {
+            Some(Ok(a)) => a,
+            Some(Err(e)) => {
+                self.errors.push(e);
+                Ty::Unknown
+            },
+            None => Ty::Unknown,
+        }
+    }
+
+    /// 新しく推論中の型を作成します。
+    fn fresh(&mut self) -> Ty {
+        self.substitution.push(None);
+        Ty::Variable(self.substitution.len() - 1)
+    }
+
+    /// 推論中の型を、推論された型に置き換えます。置き換えは外側の型のみに行います。
+    /// 最後に置き換えた型が推論された値の範囲も返します。
+    /// - `ty` - 置き換える型
+    fn shallow(&self, ty: &Ty) -> (Ty, Option<Span>) {
+        let mut ty = ty.clone();
+        let mut origin = None;
+        while let Ty::Variable(a) = ty {
+            match &self.substitution[a] {
+                Some((b, span)) => {
+                    ty = b.clone();
+                    origin = Some(*span);
+                },
+                None => break,
+            }
+        }
+        (ty, origin)
+    }
+
+    /// 型に含まれるすべての推論中の型を、推論された型に置き換えます。
+    /// - `ty` - 置き換える型
+    fn zonk(&self, ty: &Ty) -> Ty {
+        match self.shallow(ty).0 {
+            Ty::Reference(kind, a) => Ty::Reference(kind, Box::new(self.zonk(&a))),
+            Ty::Tuple(a) => Ty::Tuple(a.iter().map(|a| self.zonk(a)).collect()),
+            Ty::Array(a, length) => Ty::Array(Box::new(self.zonk(&a)), length),
+            Ty::Vec(a) => Ty::Vec(Box::new(self.zonk(&a))),
+            Ty::Box(a) => Ty::Box(Box::new(self.zonk(&a))),
+            a => a,
+        }
+    }
+
+    /// 推論中の型が、別の型の中に含まれるかを取得します。
+    /// - `variable` - 推論中の型の番号
+    /// - `ty` - 調べる型
+    fn occurs(&self, variable: usize, ty: &Ty) -> bool {
+        match self.shallow(ty).0 {
+            Ty::Variable(a) => a == variable,
+            Ty::Reference(_, a) | Ty::Array(a, _) | Ty::Vec(a) | Ty::Box(a) => self.occurs(variable, &a),
+            Ty::Tuple(a) => a.iter().any(|a| self.occurs(variable, a)),
+            _ => false,
+        }
+    }
+
+    /// 期待される型と使用された値の型を単一化します。
+    /// 推論中の型はもう片方の型に決定し、その由来として値の範囲を記録します。可変参照は共有参照として使用できます。
+    /// - `expected` - 期待される型
+    /// - `found` - 使用された値の型
+    /// - `span` - 使用された値の範囲
+    fn unify(&mut self, expected: &Ty, found: &Ty, span: Span) -> Result<(), Conflict> {
+        let (expected, expected_at) = self.shallow(expected);
+        let (found, found_at) = self.shallow(found);
+        match (&expected, &found) {
+            (Ty::Unknown | Ty::Never, _) | (_, Ty::Unknown | Ty::Never) => Ok(()),
+            (Ty::Variable(a), Ty::Variable(b)) if a == b => Ok(()),
+            (Ty::Variable(a), b) | (b, Ty::Variable(a)) => {
+                if self.occurs(*a, b) {
+                    return Err(Conflict { expected_at, found_at });
+                }
+                trace!("Type variable {} was inferred as {} at {}.", a, b, span);
+                self.substitution[*a] = Some((b.clone(), span));
+                Ok(())
+            },
+            (Ty::Reference(BorrowKind::Shared, a), Ty::Reference(_, b))
+            | (Ty::Reference(BorrowKind::Mutable, a), Ty::Reference(BorrowKind::Mutable, b)) => {
+                self.unify(a, b, span).map_err(|e| Conflict {
+                    expected_at: expected_at.or(e.expected_at),
+                    found_at: found_at.or(e.found_at),
+                })
+            },
+            (Ty::Tuple(a), Ty::Tuple(b)) if a.len() == b.len() => {
+                for (a, b) in a.iter().zip(b) {
+                    self.unify(a, b, span).map_err(|e| Conflict {
+                        expected_at: expected_at.or(e.expected_at),
+                        found_at: found_at.or(e.found_at),
+                    })?;
+                }
+                Ok(())
+            },
+            (Ty::Array(a, m), Ty::Array(b, n)) if m == n => {
+                self.unify(a, b, span).map_err(|e| Conflict {
+                    expected_at: expected_at.or(e.expected_at),
+                    found_at: found_at.or(e.found_at),
+                })
+            },
+            (Ty::Vec(a), Ty::Vec(b)) | (Ty::Box(a), Ty::Box(b)) => {
+                self.unify(a, b, span).map_err(|e| Conflict {
+                    expected_at: expected_at.or(e.expected_at),
+                    found_at: found_at.or(e.found_at),
+                })
+            },
+            (a, b) if a == b => Ok(()),
+            _ => Err(Conflict { expected_at, found_at }),
+        }
+    }
+
+    /// 値の型が期待される型と一致することを確認します。一致しなければ、推論された型の由来と共にエラーを返します。
+    /// - `expected` - 期待される型
+    /// - `found` - 使用された値の型
+    /// - `span` - 使用された値の範囲
+    fn expect(&mut self, expected: &Ty, found: &Ty, span: Span) -> Result<(), ParseError> {
+        if let Err(conflict) = self.unify(expected, found, span) {
+            let error = MismatchedTypesError::new(self.zonk(expected).to_string(), self.zonk(found).to_string(), span);
+            ret_err!(error.with_origins(conflict.expected_at, conflict.found_at))
+        }
+        Ok(())
+    }
+
+    /// 関数の本体を検査し、本体の値が戻り値の型と一致するかを確認します。
+    /// - `function` - 検査する関数
+    fn check_function(&mut self, function: &Function) {
+        let signature = self.functions[&function.name].clone();
+        for (a, b) in function.parameters.iter().zip(signature.parameters) {
+            self.bindings.insert(a.binding, b);
+        }
+        self.output = Some(signature.output.clone());
+        let ty = self.check_block(&function.body);
+        let span = function.body.tail.as_ref().map_or(function.body.end, |a| a.span);
+        if let Err(e) = self.expect(&signature.output, &ty, span) {
+            self.errors.push(e);
+        }
+    }
+
+    /// 変数を宣言します。型注釈があればその型を、なければ推論中の型を変数の型とします。
+    /// - `declaration` - 変数の宣言
+    fn declare(&mut self, declaration: &Declaration) {
+        let ty = match &declaration.ty {
+            Some(_) => self.resolve_or_unknown(declaration.ty.as_ref()),
+            None => self.fresh(),
+        };
+        self.bindings.insert(declaration.binding, ty);
+    }
+
+    /// ブロックを検査し、ブロックの値の型を返します。
+    /// 文の検査でエラーが見つかった場合はエラーを記録し、次の文から検査を続けます。
+    /// - `block` - 検査するブロック
+    fn check_block(&mut self, block: &Block) -> Ty {
+        let mut diverges = false;
+        for a in &block.statements {
+            let result = match a {
+                Statement::Let(d, initializer) => {
+                    self.declare(d);
+                    match initializer {
+                        Some(a) => self.check_expression(a),
+                        None => Ok(Ty::Unit),
+                    }
+                },
+                Statement::Debug(e) | Statement::Expression(e) => self.check_expression(e),
+            };
+            match result {
+                Ok(Ty::Never) => diverges = true,
+                Ok(_) => {},
+                Err(e) => self.errors.push(e),
+            }
+        }
+        match &block.tail {
+            Some(a) => match self.check_expression(a) {
+                Ok(a) => a,
+                Err(e) => {
+                    self.errors.push(e);
+                    Ty::Unknown
+                },
+            },
+            None if diverges => Ty::Never,
+            None => Ty::Unit,
+        }
+    }
+
+    /// 式を検査し、式の型を返します。
+    /// - `expression` - 検査する式
+    fn check_expression(&mut self, expression: &Expression) -> Result<Ty, ParseError> {
+        trace!("Type checking {} at {}.", expression.id, expression.span);
+        match &expression.kind {
+            ExpressionKind::Integer(_) => Ok(Ty::Integer),
+            ExpressionKind::String(_) => Ok(Ty::String),
+            ExpressionKind::Variable(v) => Ok(self.bindings.get(&v.binding).cloned().unwrap_or(Ty::Unknown)),
+            ExpressionKind::Block(b) => Ok(self.check_block(b)),
+            ExpressionKind::Reference(kind, e) => Ok(Ty::Reference(*kind, Box::new(self.check_expression(e)?))),
+            ExpressionKind::Dereference(e) => {
+                let ty = self.check_expression(e)?;
+                match self.shallow(&ty).0 {
+                    Ty::Reference(_, a) | Ty::Box(a) => Ok(*a),
+                    // 参照先の型が分からなければ推論しない
+                    Ty::Unknown | Ty::Never | Ty::Variable(_) => Ok(Ty::Unknown),
+                    _ => ret_err!(ReferenceError::invalid_dereference(e.span)),
+                }
+            },
+            ExpressionKind::Unary(op, e) => {
+                let ty = self.check_expression(e)?;
+                let (resolved, _) = self.shallow(&ty);
+                if resolved.is_known() && resolved != Ty::Integer {
+                    ret_err!(InvalidOperandError::unary(op.clone(), self.zonk(&resolved).to_string(), expression.span))
+                }
+                self.expect(&Ty::Integer, &ty, e.span)?;
+                Ok(Ty::Integer)
+            },
+            ExpressionKind::Binary(op, l, r) => {
+                let left = self.check_expression(l)?;
+                let right = self.check_expression(r)?;
+                match op.as_str() {
+                    "==" | "!=" => {
+                        // 構造体と構造体を含む値は比較できない
+                        if !self.is_comparable(&left) {
+                            ret_err!(InvalidOperandError::binary(op.clone(), self.zonk(&left).to_string(), expression.span))
+                        }
+                        self.expect(&left, &right, r.span)?;
+                        Ok(Ty::Integer)
+                    },
+                    _ => self.check_operands(op, &left, &right, expression, r),
+                }
+            },
+            ExpressionKind::Struct(name, fields) => self.check_struct(name, fields, expression),
+            ExpressionKind::Field(e, name, span) => {
+                let ty = self.check_expression(e)?;
+                self.field(&ty, name, *span)
+            },
+            ExpressionKind::Tuple(elements) if elements.is_empty() => Ok(Ty::Unit),
+            ExpressionKind::Tuple(elements) => Ok(Ty::Tuple(elements.iter().map(|a| self.check_expression(a)).collect::<Result<_, _>>()?)),
+            ExpressionKind::Array(elements) => {
+                // 要素の型はすべて最初の要素の型と一致する
+                let element = self.fresh();
+                for a in elements {
+                    let ty = self.check_expression(a)?;
+                    self.expect(&element, &ty, a.span)?;
+                }
+                Ok(Ty::Array(Box::new(element), elements.len()))
+            },
+            ExpressionKind::Vec(elements) => {
+                let element = self.fresh();
+                for a in elements {
+                    let ty = self.check_expression(a)?;
+                    self.expect(&element, &ty, a.span)?;
+                }
+                Ok(Ty::Vec(Box::new(element)))
+            },
+            ExpressionKind::Box(e) => Ok(Ty::Box(Box::new(self.check_expression(e)?))),
+            ExpressionKind::MethodCall(receiver, name, span, arguments) => {
+                let ty = self.check_expression(receiver)?;
+                self.check_method(&ty, name, *span, arguments, expression)
+            },
+            ExpressionKind::Index(e, index) => {
+                let ty = self.check_expression(e)?;
+                let index_ty = self.check_expression(index)?;
+                self.expect(&Ty::Integer, &index_ty, index.span)?;
+                self.element(&ty, expression.span)
+            },
+            ExpressionKind::Assign(op, target, value) => {
+                let ty = self.check_expression(value)?;
+                let target_ty = self.check_expression(target)?;
+                if op == "=" {
+                    self.expect(&target_ty, &ty, value.span)?;
+                } else {
+                    self.check_operands(op.trim_end_matches('='), &target_ty, &ty, expression, value)?;
+                }
+                Ok(Ty::Unit)
+            },
+            ExpressionKind::If(condition, then, otherwise) => {
+                self.check_condition(condition)?;
+                let then_ty = self.check_block(then);
+                let Some(otherwise) = otherwise else {
+                    return Ok(Ty::Unit);
+                };
+                let else_ty = self.check_expression(otherwise)?;
+                self.expect(&then_ty, &else_ty, otherwise.span)?;
+                // 片方の分岐に到達しなければもう片方の型とする
+                match self.shallow(&then_ty).0 {
+                    Ty::Never | Ty::Unknown => Ok(else_ty),
+                    _ => Ok(then_ty),
+                }
+            },
+            ExpressionKind::While(condition, body) => {
+                self.check_condition(condition)?;
+                self.loops.push(None);
+                self.check_block(body);
+                self.loops.pop();
+                Ok(Ty::Unit)
+            },
+            ExpressionKind::Loop(body) => {
+                // `break` がなければループの後には到達しない
+                self.loops.push(Some(Ty::Never));
+                self.check_block(body);
+                Ok(self.loops.pop().flatten().unwrap_or(Ty::Never))
+            },
+            ExpressionKind::Break(value) => {
+                let ty = match value {
+                    Some(a) => self.check_expression(a)?,
+                    None => Ty::Unit,
+                };
+                if let Some(Some(current)) = self.loops.last().cloned() {
+                    let span = value.as_ref().map_or(expression.span, |a| a.span);
+                    self.expect(&current, &ty, span)?;
+                    if matches!(current, Ty::Never) {
+                        *self.loops.last_mut().unwrap() = Some(ty);
+                    }
+                }
+                Ok(Ty::Never)
+            },
+            ExpressionKind::Continue => Ok(Ty::Never),
+            // 組み込みの関数 `drop` はどの型の値も受け取る
+            ExpressionKind::Call(name, arguments) if !self.functions.contains_key(name) => {
+                for a in arguments {
+                    self.check_expression(a)?;
+                }
+                Ok(Ty::Unit)
+            },
+            ExpressionKind::Call(name, arguments) => {
+                let signature = self.functions[name].clone();
+                for (a, expected) in arguments.iter().zip(&signature.parameters) {
+                    let ty = self.check_expression(a)?;
+                    self.expect(expected, &ty, a.span)?;
+                }
+                Ok(signature.output)
+            },
+            ExpressionKind::Return(value) => {
+                let ty = match value {
+                    Some(a) => self.check_expression(a)?,
+                    None => Ty::Unit,
+                };
+                if let Some(output) = self.output.clone() {
+                    let span = value.as_ref().map_or(expression.span, |a| a.span);
+                    self.expect(&output, &ty, span)?;
+                }
+                Ok(Ty::Never)
+            },
+        }
+    }
+
+    /// 構造体式を検査します。書かれたフィールドが定義に存在し、定義されたすべてのフィールドが書かれていることを確認します。
+    /// - `name` - 構造体名
+    /// - `fields` - 構造体式に書かれたフィールド
+    /// - `expression` - 構造体式
+    fn check_struct(&mut self, name: &str, fields: &[FieldValue], expression: &Expression) -> Result<Ty, ParseError> {
+        let definition = self.structs[name].clone();
+        for a in fields {
+            let ty = self.check_expression(&a.value)?;
+            match definition.iter().find(|(b, _)| *b == a.name) {
+                Some((_, expected)) => self.expect(expected, &ty, a.value.span)?,
+                None => ret_err!(FieldError::no_field_named(a.name.clone(), name.to_string(), a.span)),
+            }
+        }
+        let missing: Vec<String> = definition
+            .into_iter()
+            .filter(|(a, _)| !fields.iter().any(|b| b.name == *a))
+            .map(|(a, _)| a)
+            .collect();
+        if !missing.is_empty() {
+            let span = Span { end: expression.span.start + name.len(), ..expression.span };
+            ret_err!(FieldError::missing(missing, name.to_string(), span))
+        }
+        Ok(Ty::Struct(name.to_string()))
+    }
+
+    /// フィールドの型を返します。参照のフィールドは参照先のフィールドとします。
+    /// - `ty` - アクセスする値の型
+    /// - `name` - フィールド名
+    /// - `span` - フィールド名の範囲
+    fn field(&self, ty: &Ty, name: &str, span: Span) -> Result<Ty, ParseError> {
+        let mut ty = self.shallow(ty).0;
+        while let Ty::Reference(_, a) = ty {
+            ty = self.shallow(&a).0;
+        }
+        match &ty {
+            Ty::Struct(a) => match self.structs[a].iter().find(|(b, _)| b == name) {
+                Some((_, b)) => Ok(b.clone()),
+                None => ret_err!(FieldError::no_field(name.to_string(), ty.to_string(), span)),
+            },
+            Ty::Tuple(a) => match name.parse::<usize>().ok().and_then(|b| a.get(b)) {
+                Some(b) => Ok(b.clone()),
+                None => ret_err!(FieldError::no_field(name.to_string(), self.zonk(&ty).to_string(), span)),
+            },
+            // 型が分からなければ推論しない
+            Ty::Unknown | Ty::Never | Ty::Variable(_) => Ok(Ty::Unknown),
+            _ => ret_err!(FieldError::no_field(name.to_string(), self.zonk(&ty).to_string(), span)),
+        }
+    }
+
+    /// 添字によるアクセスで得られる要素の型を返します。参照の要素は参照先の要素とします。
+    /// - `ty` - アクセスする値の型
+    /// - `span` - 添字によるアクセスの範囲
+    fn element(&self, ty: &Ty, span: Span) -> Result<Ty, ParseError> {
+        let mut ty = self.shallow(ty).0;
+        while let Ty::Reference(_, a) = ty {
+            ty = self.shallow(&a).0;
+        }
+        match ty {
+            Ty::Array(a, _) | Ty::Vec(a) => Ok(*a),
+            // 型が分からなければ推論しない
+            Ty::Unknown | Ty::Never | Ty::Variable(_) => Ok(Ty::Unknown),
+            a => ret_err!(IndexError::not_indexable(self.zonk(&a).to_string(), span)),
+        }
+    }
+
+    /// 組み込みのメソッドの呼び出しを検査し、戻り値の型を返します。参照のメソッドは参照先のメソッドとします。
+    /// `Vec` の `push`、`pop`、`len` と、値を複製する `clone` を呼び出せます。
+    /// - `ty` - メソッドを呼び出す値の型
+    /// - `name` - メソッド名
+    /// - `span` - メソッド名の範囲
+    /// - `arguments` - 引数
+    /// - `expression` - メソッド呼び出しの式
+    fn check_method(&mut self, ty: &Ty, name: &str, span: Span, arguments: &[Expression], expression: &Expression) -> Result<Ty, ParseError> {
+        let mut ty = self.shallow(ty).0;
+        while let Ty::Reference(_, a) = ty {
+            ty = self.shallow(&a).0;
+        }
+        let (parameters, output) = match (&ty, name) {
+            (Ty::Vec(a), "push") => (vec![(**a).clone()], Ty::Unit),
+            (Ty::Vec(a), "pop") => (Vec::new(), (**a).clone()),
+            (Ty::Vec(_), "len") => (Vec::new(), Ty::Integer),
+            // `()` 以外の値は複製できる
+            (Ty::Integer | Ty::String | Ty::Struct(_) | Ty::Tuple(_) | Ty::Array(_, _) | Ty::Vec(_) | Ty::Box(_), "clone") => (Vec::new(), ty.clone()),
+            // 型が分からなければ推論しない
+            (Ty::Unknown | Ty::Never | Ty::Variable(_), _) => {
+                for a in arguments {
+                    self.check_expression(a)?;
+                }
+                return Ok(Ty::Unknown);
+            },
+            _ => ret_err!(MethodError::new(name.to_string(), self.zonk(&ty).to_string(), span)),
+        };
+        if parameters.len() != arguments.len() {
+            let error = InvalidExpressionError::new(format!(
+                "Method \"{}\" takes {} argument(s) but {} argument(s) were supplied.",
+                name,
+                parameters.len(),
+                arguments.len()
+            ));
+            ret_err!(error.with_span(expression.span))
+        }
+        for (a, expected) in arguments.iter().zip(&parameters) {
+            let ty = self.check_expression(a)?;
+            self.expect(expected, &ty, a.span)?;
+        }
+        Ok(output)
+    }
+
+    /// `==` と `!=` で比較できる型であるかを取得します。構造体と、構造体を含むタプルや配列は比較できません。
+    /// - `ty` - 調べる型
+    fn is_comparable(&self, ty: &Ty) -> bool {
+        match self.shallow(ty).0 {
+            Ty::Struct(_) => false,
+            Ty::Tuple(a) => a.iter().all(|a| self.is_comparable(a)),
+            Ty::Array(a, _) | Ty::Vec(a) | Ty::Box(a) => self.is_comparable(&a),
+            _ => true,
+        }
+    }
+
+    /// 条件式を検査し、整数であることを確認します。
+    /// - `condition` - 検査する条件式
+    fn check_condition(&mut self, condition: &Expression) -> Result<(), ParseError> {
+        let ty = self.check_expression(condition)?;
+        self.expect(&Ty::Integer, &ty, condition.span)
+    }
+
+    /// 算術演算子などの二項演算子の被演算子の型を検査し、演算結果の型を返します。
+    /// `+` は整数同士または文字列同士、その他の演算子は整数同士の演算のみ行えます。
+    /// - `op` - 演算子
+    /// - `left` - 左辺の型
+    /// - `right` - 右辺の型
+    /// - `expression` - 二項式
+    /// - `r` - 右辺の式
+    fn check_operands(&mut self, op: &str, left: &Ty, right: &Ty, expression: &Expression, r: &Expression) -> Result<Ty, ParseError> {
+        let (resolved, _) = self.shallow(left);
+        let ty = if resolved.is_known() { resolved } else { self.shallow(right).0 };
+        let allowed = match op {
+            "+" => matches!(ty, Ty::Integer | Ty::String),
+            _ => ty == Ty::Integer,
+        };
+        if ty.is_known() && !allowed {
+            ret_err!(InvalidOperandError::binary(op.to_string(), self.zonk(&ty).to_string(), expression.span))
+        }
+        self.expect(left, right, r.span)?;
+        if op == "+" {
+            Ok(self.shallow(left).0)
+        } else {
+            // `+` 以外の演算子の被演算子は整数に決まる
+            self.expect(&Ty::Integer, left, expression.span)?;
+            Ok(Ty::Integer)
+        }
+    }
+}
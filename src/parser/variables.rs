@@ -1,30 +1,207 @@
-use std::{fmt::Display, rc::Rc};
+use std::collections::HashMap;
+use std::fmt::Display;
 
-use log::info;
+use crate::ret_err;
 
 use super::ExprParser;
+use super::ast::{Declaration, Variable};
+use super::borrow::BorrowKind;
+use super::errors::{ParseError, VariableNotFoundError};
+use super::eval::Flow;
+use super::token::Span;
 
 impl ExprParser {
-    /// 可変な状態で変数を取得します。
-    /// - `name` - 変数名
-    pub fn get_variable_mut(&mut self, name: &String) -> Option<&mut VarType> {
-        self.variables.get_mut(name)
+    /// 変数が格納されている領域の番号を取得します。
+    /// 実行中の関数のスコープのみを、内側のスコープから順に探します。
+    /// - `binding` - 変数を識別する番号
+    pub fn get_slot(&self, binding: usize) -> Option<usize> {
+        let base = self.frames.last().map_or(0, |a| a.base);
+        self.scopes[base..].iter().rev().find_map(|a| a.slots.get(&binding).copied())
     }
-    
+
     /// 変数を取得します。
-    /// - `name` - 変数名
-    pub fn get_variable(&self, name: &String) -> Option<&VarType> {
-        self.variables.get(name)
+    /// - `variable` - 式の中で使用された変数
+    /// - `span` - 変数が使用された範囲
+    pub fn get_variable(&self, variable: &Variable, span: Span) -> Result<&Binding, ParseError> {
+        match self.get_slot(variable.binding) {
+            Some(a) => Ok(&self.slots[a]),
+            None => ret_err!(VariableNotFoundError::new(variable.name.clone(), span)),
+        }
+    }
+
+    /// 現在のスコープに変数を作成します。
+    /// - `declaration` - 変数の宣言
+    pub fn create_variable(&mut self, declaration: &Declaration) {
+        let slot = self.slots.len();
+        self.scopes.last_mut().unwrap().slots.insert(declaration.binding, slot);
+        self.slots.push(Binding::new(declaration.name.clone(), VarType::Uninitialized));
+    }
+
+    /// 一時的な値を格納する領域を確保し、その場所を返します。
+    /// 一時的な値は現在のスコープに属し、スコープの終わりに変数と共に作成と逆の順で破棄されます。
+    /// - `value` - 格納する値
+    pub fn allocate_temporary(&mut self, value: VarType) -> Place {
+        let slot = self.slots.len();
+        self.slots.push(Binding::new(String::from(TEMPORARY_NAME), value));
+        Place { slot, name: String::from(TEMPORARY_NAME), path: Vec::new() }
+    }
+
+    /// 場所に格納されている値を取得します。
+    /// - `place` - 値の場所
+    pub fn value_at(&self, place: &Place) -> &VarType {
+        let mut value = &self.slots[place.slot].value;
+        for a in &place.path {
+            value = match value {
+                VarType::Struct(_, fields) => &fields[*a].1,
+                VarType::Tuple(elements) | VarType::Array(elements) | VarType::Vec(elements) => &elements[*a],
+                VarType::Box(a) => a,
+                _ => value,
+            };
+        }
+        value
+    }
+
+    /// 場所に格納されている値を変更可能な状態で取得します。
+    /// - `place` - 値の場所
+    pub fn value_at_mut(&mut self, place: &Place) -> &mut VarType {
+        let mut value = &mut self.slots[place.slot].value;
+        for a in &place.path {
+            value = match value {
+                VarType::Struct(_, fields) => &mut fields[*a].1,
+                VarType::Tuple(elements) | VarType::Array(elements) | VarType::Vec(elements) => &mut elements[*a],
+                VarType::Box(a) => a,
+                _ => value,
+            };
+        }
+        value
+    }
+
+    /// 新しいスコープを開始します。
+    pub fn push_scope(&mut self) {
+        self.scopes.push(Scope { base: self.slots.len(), slots: HashMap::new() });
+    }
+
+    /// 現在のスコープを終了し、スコープ内の変数と一時的な値を作成と逆の順に破棄して、スコープ内の領域を解放します。
+    /// スコープの外から参照されている一時的な値は破棄せず、外側のスコープの領域へ移します。
+    /// - `value` - スコープの外へ渡される値 (ブロックの値など)
+    pub fn drop_scope(&mut self, value: Option<&mut VarType>) {
+        let base = self.scopes.pop().unwrap().base;
+        // `break` や `return` で運ばれている値も、スコープの外へ渡される
+        let mut flow = std::mem::take(&mut self.flow);
+        let mut values: Vec<&mut VarType> = value.into_iter().collect();
+        if let Flow::Break(a) | Flow::Return(a) = &mut flow {
+            values.push(a);
+        }
+        let kept = self.escaping_temporaries(base, &values);
+        let mut freed = self.slots.split_off(base);
+        for slot in &kept {
+            let binding = Binding::new(String::from(TEMPORARY_NAME), VarType::Void);
+            self.slots.push(std::mem::replace(&mut freed[slot - base], binding));
+        }
+        // 領域は変数と一時的な値が作成された順に確保される
+        for a in freed.into_iter().rev() {
+            self.drop_value(&a.name, a.value);
+        }
+        if !kept.is_empty() {
+            let relocated: HashMap<usize, usize> = kept.iter().enumerate().map(|(i, a)| (*a, base + i)).collect();
+            for a in values {
+                a.relocate(&relocated);
+            }
+            for a in &mut self.slots {
+                a.value.relocate(&relocated);
+            }
+        }
+        self.flow = flow;
+    }
+
+    /// 指定した番号以降の領域のうち、それより前の領域または外へ渡される値から参照されている一時的な値の領域を、番号順に返します。
+    /// 残す一時的な値から参照されている一時的な値も含みます。
+    /// - `base` - 解放する最初の領域の番号
+    /// - `values` - 領域の外へ渡される値
+    fn escaping_temporaries(&self, base: usize, values: &[&mut VarType]) -> Vec<usize> {
+        if !self.slots[base..].iter().any(|a| a.name == TEMPORARY_NAME) {
+            return Vec::new();
+        }
+        let mut pending = Vec::new();
+        for a in values.iter().map(|a| &**a).chain(self.slots[..base].iter().map(|a| &a.value)) {
+            a.referenced_slots(&mut pending);
+        }
+        let mut kept = Vec::new();
+        while let Some(slot) = pending.pop() {
+            if slot >= base && slot < self.slots.len() && self.slots[slot].name == TEMPORARY_NAME && !kept.contains(&slot) {
+                kept.push(slot);
+                self.slots[slot].value.referenced_slots(&mut pending);
+            }
+        }
+        kept.sort_unstable();
+        kept
     }
 
-    /// 変数を作成します。
-    /// - `name` - 新しく作成する変数名
-    pub fn create_variable(&mut self, name: String){
-        info!("Variable \"{}\" was created.", name);
-        if !self.variables.contains_key(&name) {
-            self.variables.insert(name, VarType::Uninitialized);
+    /// 値を破棄します。ヒープ上の値を所有する値を破棄した場合は、破棄した値を表示します。
+    /// - `name` - 値を所有していた変数名
+    /// - `value` - 破棄する値
+    pub fn drop_value(&mut self, name: &str, value: VarType) {
+        if value.owns_heap() {
+            self.print(format!("Drop({}): {:?}", name, value));
         }
     }
+
+    /// 評価中の出力を一行表示し、表示した行として記録します。
+    /// - `line` - 表示する行
+    pub fn print(&mut self, line: String) {
+        println!("{}", line);
+        self.output.push(line);
+    }
+}
+
+/// 関数呼び出しの情報を保持する構造体です。
+#[derive(Debug, Clone)]
+pub struct Frame {
+    /// 呼び出された関数名
+    pub function: String,
+    /// 関数のスコープが始まる位置
+    pub base: usize,
+}
+
+/// 変数のスコープを表す構造体です。
+#[derive(Debug, Clone)]
+pub struct Scope {
+    /// スコープが始まる位置の領域の番号 (スコープ内の変数と一時的な値は、この番号以降の領域に格納されます)
+    pub base: usize,
+    /// 変数を識別する番号ごとの、変数が格納されている領域の番号
+    pub slots: HashMap<usize, usize>,
+}
+
+/// 一時的な値を格納する領域の名前です。
+pub const TEMPORARY_NAME: &str = "{temporary}";
+
+/// 変数の値を保持する構造体です。
+#[derive(Debug, Clone)]
+pub struct Binding {
+    /// 変数名
+    pub name: String,
+    /// 変数に格納されている値
+    pub value: VarType,
+}
+
+impl Binding {
+    /// 新しく変数を作成します。
+    /// - `name` - 変数名
+    /// - `value` - 変数の初期値
+    pub fn new(name: String, value: VarType) -> Self {
+        Binding { name, value }
+    }
+}
+
+/// 参照先となる場所を表す構造体です。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Place {
+    /// 値が格納されている領域の番号
+    pub slot: usize,
+    /// 領域を所有する変数名 (フィールドであれば `a.b` の形式)
+    pub name: String,
+    /// 領域に格納された値の中での、外側から順のフィールドまたは要素の位置
+    pub path: Vec<usize>,
 }
 
 /// 変数として格納可能な値を保持する構造体です。
@@ -39,22 +216,86 @@ pub enum VarType {
     /// 文字列であることを表します。
     String(String),
     /// ポインタであることを表します。
-    Pointer(Rc<VarType>),
+    Pointer(Reference),
+    /// 構造体であることを表します。フィールドは定義された順に保持します。
+    Struct(String, Vec<(String, VarType)>),
+    /// タプルであることを表します。
+    Tuple(Vec<VarType>),
+    /// 配列であることを表します。
+    Array(Vec<VarType>),
+    /// 可変長の配列であることを表します。
+    Vec(Vec<VarType>),
+    /// ヒープ上の値を所有するポインタであることを表します。
+    Box(Box<VarType>),
 }
 
 impl VarType {
     /// 値が空であるかを取得する関数です。
     pub fn is_empty(&self) -> bool {
+        matches!(self, VarType::Uninitialized | VarType::Void)
+    }
+
+    /// エラーメッセージに表示する型名を返します。
+    pub fn type_name(&self) -> String {
+        match self {
+            VarType::Uninitialized | VarType::Void => "()".to_string(),
+            VarType::Integer(_) => "i32".to_string(),
+            VarType::String(_) => "String".to_string(),
+            VarType::Pointer(a) if a.kind == BorrowKind::Mutable => "&mut _".to_string(),
+            VarType::Pointer(_) => "&_".to_string(),
+            VarType::Struct(a, _) => a.clone(),
+            VarType::Tuple(a) if a.len() == 1 => format!("({},)", a[0].type_name()),
+            VarType::Tuple(a) => format!("({})", a.iter().map(|a| a.type_name()).collect::<Vec<String>>().join(", ")),
+            VarType::Array(a) => format!("[{}; {}]", a.first().map_or("_".to_string(), |a| a.type_name()), a.len()),
+            VarType::Vec(a) => format!("Vec<{}>", a.first().map_or("_".to_string(), |a| a.type_name())),
+            VarType::Box(a) => format!("Box<{}>", a.type_name()),
+        }
+    }
+
+    /// 文字列、可変長の配列、`Box` のように、ヒープ上の値を所有する値を含むかを取得する関数です。
+    pub fn owns_heap(&self) -> bool {
+        match self {
+            VarType::String(_) | VarType::Vec(_) | VarType::Box(_) => true,
+            VarType::Struct(_, fields) => fields.iter().any(|(_, a)| a.owns_heap()),
+            VarType::Tuple(elements) | VarType::Array(elements) => elements.iter().any(VarType::owns_heap),
+            _ => false,
+        }
+    }
+
+    /// 値に含まれる参照の参照先の領域の番号を追加します。
+    /// - `slots` - 領域の番号を追加する配列
+    pub fn referenced_slots(&self, slots: &mut Vec<usize>) {
+        match self {
+            VarType::Pointer(a) => slots.push(a.place.slot),
+            VarType::Struct(_, fields) => fields.iter().for_each(|(_, a)| a.referenced_slots(slots)),
+            VarType::Tuple(elements) | VarType::Array(elements) | VarType::Vec(elements) => elements.iter().for_each(|a| a.referenced_slots(slots)),
+            VarType::Box(a) => a.referenced_slots(slots),
+            _ => {},
+        }
+    }
+
+    /// 値に含まれる参照の参照先を、移した先の領域に置き換えます。
+    /// - `relocated` - 移す前の領域の番号ごとの、移した先の領域の番号
+    pub fn relocate(&mut self, relocated: &HashMap<usize, usize>) {
         match self {
-            VarType::Uninitialized | VarType::Void => true,
-            _ => false
+            VarType::Pointer(a) => if let Some(b) = relocated.get(&a.place.slot) {
+                a.place.slot = *b;
+            },
+            VarType::Struct(_, fields) => fields.iter_mut().for_each(|(_, a)| a.relocate(relocated)),
+            VarType::Tuple(elements) | VarType::Array(elements) | VarType::Vec(elements) => elements.iter_mut().for_each(|a| a.relocate(relocated)),
+            VarType::Box(a) => a.relocate(relocated),
+            _ => {},
         }
     }
 
-    /// 新しくVarType::Stringを作成します。
-    /// * `data` - 文字列の内容
-    pub fn new_string(data: &str) -> Self {
-        VarType::String(data.trim_matches('"').to_string())
+    /// 構造体のフィールドまたはタプルの要素の位置を取得します。存在しなければ `None` を返します。
+    /// - `name` - フィールド名、またはタプルの要素の番号
+    pub fn field_index(&self, name: &str) -> Option<usize> {
+        match self {
+            VarType::Struct(_, fields) => fields.iter().position(|(a, _)| a == name),
+            VarType::Tuple(elements) => name.parse().ok().filter(|a| *a < elements.len()),
+            _ => None,
+        }
     }
 }
 
@@ -71,4 +312,37 @@ impl PartialOrd for VarType {
             _ => None
         }
     }
-}
\ No newline at end of file
+}
+
+/// 参照の値を保持する構造体です。
+#[derive(Clone)]
+pub struct Reference {
+    /// 参照先の場所
+    pub place: Place,
+    /// 借用の種類
+    pub kind: BorrowKind,
+}
+
+impl Reference {
+    /// 新しく参照を作成します。
+    /// - `place` - 参照先の場所
+    /// - `kind` - 借用の種類
+    pub fn new(place: Place, kind: BorrowKind) -> Self {
+        Reference { place, kind }
+    }
+}
+
+impl std::fmt::Debug for Reference {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.kind {
+            BorrowKind::Shared => write!(f, "&{}", self.place.name),
+            BorrowKind::Mutable => write!(f, "&mut {}", self.place.name),
+        }
+    }
+}
+
+impl PartialEq for Reference {
+    fn eq(&self, other: &Self) -> bool {
+        self.place == other.place
+    }
+}